@@ -0,0 +1,44 @@
+/// Replays a binary log written with `LoggerConfig::binary_file` in a window
+///
+/// `cargo run --example replay --features amethyst-system,binary -- session.bin`
+
+use amethyst::{
+    input::{InputBundle, StringBindings},
+    prelude::*,
+    renderer::{bundle::RenderingBundle, types::DefaultBackend, RenderToWindow},
+    utils::application_root_dir,
+};
+
+use imgui_log::{amethyst_imgui::RenderImgui, LoggerConfig};
+
+struct Replay;
+impl SimpleState for Replay {}
+
+fn main() -> amethyst::Result<()> {
+    let path = std::env::args().nth(1).expect("usage: replay <binary log>");
+
+    let app_root = application_root_dir()?;
+    let display_config_path = app_root.join("examples/display.ron");
+
+    // Only show the replayed lines, not amethyst's own
+    let mut system =
+        imgui_log::create_system_with_config(LoggerConfig::default().install_global(false));
+    system.window_mut().load_binary(&path)?;
+
+    let game_data = GameDataBuilder::default()
+        .with_barrier()
+        .with(system, "imgui_log", &[])
+        .with_bundle(InputBundle::<StringBindings>::default())?
+        .with_bundle(
+            RenderingBundle::<DefaultBackend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config_path)
+                        .with_clear([0.34, 0.36, 0.52, 1.0]),
+                )
+                .with_plugin(RenderImgui::<StringBindings>::default()),
+        )?;
+
+    Application::build("/", Replay)?.build(game_data)?.run();
+
+    Ok(())
+}
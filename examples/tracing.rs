@@ -0,0 +1,63 @@
+/// Shows `tracing` events in the log window through `imgui_log::LogLayer`
+///
+/// `cargo run --example tracing --features amethyst-system,tracing`
+
+use amethyst::{
+    ecs::System,
+    input::{InputBundle, StringBindings},
+    prelude::*,
+    renderer::{bundle::RenderingBundle, types::DefaultBackend, RenderToWindow},
+    utils::application_root_dir,
+};
+
+use imgui_log::{amethyst_imgui::RenderImgui, LogSystem, LoggerConfig};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[derive(Default)]
+pub struct TickSystem {
+    tick: u64,
+}
+
+impl<'s> System<'s> for TickSystem {
+    type SystemData = ();
+
+    fn run(&mut self, _: Self::SystemData) {
+        self.tick += 1;
+        if self.tick % 60 == 0 {
+            tracing::info!(tick = self.tick, "a second went by");
+        }
+        if self.tick % 300 == 0 {
+            tracing::warn!(tick = self.tick, behind_ms = 12, "frame took too long");
+        }
+    }
+}
+
+struct Example;
+impl SimpleState for Example {}
+
+fn main() -> amethyst::Result<()> {
+    let app_root = application_root_dir()?;
+    let display_config_path = app_root.join("examples/display.ron");
+
+    let (window, layer) = imgui_log::init_tracing(LoggerConfig::default());
+    let subscriber = tracing_subscriber::Registry::default().with(layer);
+    tracing::subscriber::set_global_default(subscriber).expect("a subscriber was already set");
+
+    let game_data = GameDataBuilder::default()
+        .with_barrier()
+        .with(TickSystem::default(), "tick_system", &[])
+        .with(LogSystem::new(window), "imgui_log", &[])
+        .with_bundle(InputBundle::<StringBindings>::default())?
+        .with_bundle(
+            RenderingBundle::<DefaultBackend>::new()
+                .with_plugin(
+                    RenderToWindow::from_config_path(display_config_path)
+                        .with_clear([0.34, 0.36, 0.52, 1.0]),
+                )
+                .with_plugin(RenderImgui::<StringBindings>::default()),
+        )?;
+
+    Application::build("/", Example)?.build(game_data)?.run();
+
+    Ok(())
+}
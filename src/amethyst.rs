@@ -2,7 +2,6 @@ pub use amethyst_imgui;
 
 use crate::{LogWindow, LoggerConfig};
 use amethyst::ecs::System;
-use imgui::im_str;
 
 fn format_line(record: &log::Record) -> String {
     let location = if let (Some(file), Some(line)) = (record.file(), record.line()) {
@@ -12,31 +11,74 @@ fn format_line(record: &log::Record) -> String {
     };
 
     let msg = record.args().to_string();
-    unsafe {
-        if let Some(ui) = amethyst_imgui::current_ui() {
-            format!(
-                "[{:05}][{:.1}s] {} --- {}: {}\n",
-                ui.frame_count(),
-                ui.time(),
-                location,
-                record.level(),
-                msg
-            )
-        } else {
-            format!("{} --- {}: {}\n", location, record.level(), msg)
-        }
-    }
+    format!("{} --- {}: {}\n", location, record.level(), msg)
+}
+
+fn current_frame() -> Option<(u64, f32)> {
+    unsafe { amethyst_imgui::current_ui().map(|ui| (ui.frame_count() as u64, ui.time() as f32)) }
 }
 
 /// Draws a LogWindow every frame
 pub struct LogSystem {
     open: bool,
     log: LogWindow,
+    auto_open: bool,
+    /// Set once the window was opened for an error, until re-armed
+    auto_opened: bool,
+    setup: Option<fn(imgui::Window) -> imgui::Window>,
+    position: Option<[f32; 2]>,
+    size: Option<[f32; 2]>,
 }
 
 impl LogSystem {
     pub fn new(log: LogWindow) -> Self {
-        LogSystem { open: true, log }
+        LogSystem {
+            open: true,
+            log,
+            auto_open: false,
+            auto_opened: false,
+            setup: None,
+            position: None,
+            size: None,
+        }
+    }
+
+    /// The window being drawn by this system
+    pub fn window(&self) -> &LogWindow {
+        &self.log
+    }
+
+    /// Show or hide the window, it can also be closed with its close button
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Open the window the first time an error is logged, e.g. to keep the console hidden
+    /// in diagnostic builds until something goes wrong.
+    ///
+    /// This only happens once so the window can be closed again without popping back up.
+    /// Calling this again re-arms it.
+    pub fn set_auto_open_on_error(&mut self, auto_open: bool) {
+        self.auto_open = auto_open;
+        self.auto_opened = false;
+    }
+
+    /// Adjust the window before it is drawn each frame, e.g. its initial position,
+    /// size or flags so it fits into your editor layout.
+    ///
+    /// Docking is not available in the imgui version used by `amethyst-imgui`,
+    /// but this is where the window flags or dock settings would go with a build that has it.
+    pub fn set_window_setup(&mut self, setup: fn(imgui::Window) -> imgui::Window) {
+        self.setup = Some(setup);
+    }
+
+    /// The window being drawn by this system, to change its settings or clear it
+    pub fn window_mut(&mut self) -> &mut LogWindow {
+        &mut self.log
     }
 }
 
@@ -45,17 +87,54 @@ impl<'s> System<'s> for LogSystem {
 
     fn run(&mut self, _: Self::SystemData) {
         amethyst_imgui::with(|ui| {
-            let window = imgui::Window::new(im_str!("Console Log")).opened(&mut self.open);
+            let name = self.log.name().unwrap_or("Console Log");
+            // Same id either way so the window keeps its position
+            let title = if self.log.has_unseen_errors() {
+                imgui::ImString::new(format!("{} (!)###{}", name, name))
+            } else {
+                imgui::ImString::new(format!("{}###{}", name, name))
+            };
+            let mut window = imgui::Window::new(&title).opened(&mut self.open);
+            if let Some(position) = self.position {
+                window = window.position(position, imgui::Condition::FirstUseEver);
+            }
+            if let Some(size) = self.size {
+                window = window.size(size, imgui::Condition::FirstUseEver);
+            }
+            if let Some(setup) = self.setup {
+                window = setup(window);
+            }
             self.log.build(ui, window);
+
+            if self.auto_open && !self.auto_opened && self.log.has_unseen_errors() {
+                self.open = true;
+                self.auto_opened = true;
+            }
         });
     }
 }
 
 /// Creates a customized system that will display your logs in a window.
 /// This will automatically initialize the logger
+///
+/// Lines are formatted with the location and level unless `config` has a formatter.
 pub fn create_system_with_config(config: LoggerConfig) -> LogSystem {
-    let log_window = crate::init_with_config(config.formatter(format_line));
-    LogSystem::new(log_window)
+    let open = config.start_open;
+    let position = config.window_position;
+    let size = config.window_size;
+    // Keep a formatter the caller chose, e.g. a compact one for the window
+    let config = if config.formatter.is_none() {
+        config.formatter(format_line)
+    } else {
+        config
+    };
+    let log_window = crate::init_with_config(config.frame_source(current_frame));
+
+    let mut system = LogSystem::new(log_window);
+    system.open = open;
+    system.position = position;
+    system.size = size;
+    system
 }
 
 /// Creates a system that will display your logs every frame.
@@ -0,0 +1,89 @@
+use crate::LogColors;
+use log::Level;
+
+/// The 16 standard terminal colors as (foreground code, approximate rgb)
+const PALETTE: [(u8, [f32; 3]); 16] = [
+    (30, [0., 0., 0.]),
+    (31, [0.5, 0., 0.]),
+    (32, [0., 0.5, 0.]),
+    (33, [0.5, 0.5, 0.]),
+    (34, [0., 0., 0.5]),
+    (35, [0.5, 0., 0.5]),
+    (36, [0., 0.5, 0.5]),
+    (37, [0.75, 0.75, 0.75]),
+    (90, [0.5, 0.5, 0.5]),
+    (91, [1., 0., 0.]),
+    (92, [0., 1., 0.]),
+    (93, [1., 1., 0.]),
+    (94, [0., 0., 1.]),
+    (95, [1., 0., 1.]),
+    (96, [0., 1., 1.]),
+    (97, [1., 1., 1.]),
+];
+
+/// Colors used when mirroring to stdout
+///
+/// Each value is an ANSI foreground color code (30-37, 90-97).
+/// Use `StdoutColors::from(log_colors)` to approximate a window palette.
+#[derive(Clone, Copy)]
+pub struct StdoutColors {
+    pub trace: u8,
+    pub debug: u8,
+    pub info: u8,
+    pub warn: u8,
+    pub error: u8,
+}
+
+impl Default for StdoutColors {
+    fn default() -> Self {
+        StdoutColors::from(LogColors::default())
+    }
+}
+
+impl From<LogColors> for StdoutColors {
+    fn from(colors: LogColors) -> Self {
+        StdoutColors {
+            trace: nearest(colors.trace),
+            debug: nearest(colors.debug),
+            info: nearest(colors.info),
+            warn: nearest(colors.warn),
+            error: nearest(colors.error),
+        }
+    }
+}
+
+impl StdoutColors {
+    pub fn level(&self, level: Level) -> u8 {
+        match level {
+            Level::Trace => self.trace,
+            Level::Debug => self.debug,
+            Level::Info => self.info,
+            Level::Warn => self.warn,
+            Level::Error => self.error,
+        }
+    }
+
+    /// Wrap `text` in the escape codes for `level`
+    pub fn paint(&self, level: Level, text: &str) -> String {
+        format!("\x1b[{}m{}\x1b[0m", self.level(level), text)
+    }
+}
+
+/// Closest ANSI color code to an imgui rgba color (alpha is ignored)
+fn nearest(color: [f32; 4]) -> u8 {
+    let distance = |rgb: &[f32; 3]| {
+        (0..3)
+            .map(|i| (rgb[i] - color[i]) * (rgb[i] - color[i]))
+            .sum::<f32>()
+    };
+
+    PALETTE
+        .iter()
+        .min_by(|(_, a), (_, b)| {
+            distance(a)
+                .partial_cmp(&distance(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+        .map(|(code, _)| *code)
+        .unwrap()
+}
@@ -0,0 +1,267 @@
+use crate::diagnostics::Diagnostics;
+use crate::LogLine;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+#[cfg(feature = "ui")]
+use log::Level;
+#[cfg(feature = "ui")]
+use std::fs;
+#[cfg(feature = "ui")]
+use std::time::Duration;
+
+/// Start of every binary log, followed by the records.
+///
+/// Each record is a little endian `u32` length followed by that many bytes:
+/// the level, the timestamp (seconds and nanoseconds since the unix epoch),
+/// the optional frame, elapsed time and color, then the target, text, fields, message
+/// and attachment. Strings and attachments are a `u32` length followed by the bytes.
+/// Records written before attachments were stored end after the message.
+const MAGIC: &[u8; 8] = b"IMGLOG01";
+
+/// A file that lines are appended to in the binary format, see `LoggerConfig::binary_file`
+pub(crate) struct BinarySink {
+    path: PathBuf,
+    file: Mutex<File>,
+    /// The last write failed, so the next failure isn't reported again
+    failing: AtomicBool,
+    diagnostics: Diagnostics,
+}
+
+impl BinarySink {
+    pub fn open(path: &Path, diagnostics: &Diagnostics) -> io::Result<Self> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            file.write_all(MAGIC)?;
+        }
+        Ok(BinarySink {
+            path: path.to_owned(),
+            file: Mutex::new(file),
+            failing: AtomicBool::new(false),
+            diagnostics: diagnostics.clone(),
+        })
+    }
+
+    pub fn write(&self, line: &LogLine) {
+        let record = encode(line);
+        let mut file = match self.file.lock() {
+            Ok(file) => file,
+            Err(_) => return,
+        };
+        let result = file.write_all(&record);
+        self.diagnostics
+            .report_write(&self.failing, &self.path, result);
+    }
+}
+
+fn encode(line: &LogLine) -> Vec<u8> {
+    let mut out = vec![0; 4];
+    out.push(line.level as u8);
+
+    let since_epoch = line
+        .timestamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    out.extend_from_slice(&since_epoch.as_secs().to_le_bytes());
+    out.extend_from_slice(&since_epoch.subsec_nanos().to_le_bytes());
+
+    match line.frame {
+        Some(frame) => {
+            out.push(1);
+            out.extend_from_slice(&frame.to_le_bytes());
+        }
+        None => out.push(0),
+    }
+    match line.elapsed {
+        Some(elapsed) => {
+            out.push(1);
+            out.extend_from_slice(&elapsed.to_le_bytes());
+        }
+        None => out.push(0),
+    }
+    match line.color {
+        Some(color) => {
+            out.push(1);
+            for channel in &color {
+                out.extend_from_slice(&channel.to_le_bytes());
+            }
+        }
+        None => out.push(0),
+    }
+
+    write_str(&mut out, &line.target);
+    write_str(&mut out, &line.text);
+    out.extend_from_slice(&(line.fields.len() as u32).to_le_bytes());
+    for (key, value) in &line.fields {
+        write_str(&mut out, key);
+        write_str(&mut out, value);
+    }
+    match &line.message {
+        Some(message) => {
+            out.push(1);
+            write_str(&mut out, message);
+        }
+        None => out.push(0),
+    }
+    match &line.attachment {
+        Some(data) => {
+            out.push(1);
+            write_bytes(&mut out, data);
+        }
+        None => out.push(0),
+    }
+
+    let len = (out.len() - 4) as u32;
+    out[..4].copy_from_slice(&len.to_le_bytes());
+    out
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_bytes(out, s.as_bytes());
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// Every line in a file written by `BinarySink`, for `LogWindow::load_binary`
+#[cfg(feature = "ui")]
+pub(crate) fn read(path: &Path) -> io::Result<Vec<LogLine>> {
+    let bytes = fs::read(path)?;
+    if !bytes.starts_with(MAGIC) {
+        return Err(invalid("not an imgui-log binary log"));
+    }
+
+    let mut reader = Reader(&bytes[MAGIC.len()..]);
+    let mut lines = vec![];
+    while !reader.0.is_empty() {
+        let len = reader.u32()? as usize;
+        let mut record = Reader(reader.take(len)?);
+        lines.push(record.line()?);
+    }
+    Ok(lines)
+}
+
+#[cfg(feature = "ui")]
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(feature = "ui")]
+struct Reader<'a>(&'a [u8]);
+
+#[cfg(feature = "ui")]
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> io::Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(invalid("truncated record"));
+        }
+        let (taken, rest) = self.0.split_at(n);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> io::Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> io::Result<u32> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.take(4)?);
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn u64(&mut self) -> io::Result<u64> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn f32(&mut self) -> io::Result<f32> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    fn bytes(&mut self) -> io::Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> io::Result<String> {
+        String::from_utf8(self.bytes()?.to_vec()).map_err(|_| invalid("invalid UTF-8"))
+    }
+
+    fn line(&mut self) -> io::Result<LogLine> {
+        let level = match self.u8()? {
+            1 => Level::Error,
+            2 => Level::Warn,
+            3 => Level::Info,
+            4 => Level::Debug,
+            5 => Level::Trace,
+            _ => return Err(invalid("invalid level")),
+        };
+        let secs = self.u64()?;
+        let nanos = self.u32()?;
+        let frame = if self.u8()? == 1 {
+            Some(self.u64()?)
+        } else {
+            None
+        };
+        let elapsed = if self.u8()? == 1 {
+            Some(self.f32()?)
+        } else {
+            None
+        };
+        let color = if self.u8()? == 1 {
+            Some([self.f32()?, self.f32()?, self.f32()?, self.f32()?])
+        } else {
+            None
+        };
+        let target = self.string()?;
+        let text = self.string()?;
+        let count = self.u32()?;
+        let fields = (0..count)
+            .map(|_| Ok((self.string()?, self.string()?)))
+            .collect::<io::Result<_>>()?;
+        let message = if self.u8()? == 1 {
+            Some(self.string()?.into())
+        } else {
+            None
+        };
+        let attachment = if !self.0.is_empty() && self.u8()? == 1 {
+            Some(self.bytes()?.into())
+        } else {
+            None
+        };
+
+        let mut line = LogLine::new(level, &target, text);
+        line.timestamp = UNIX_EPOCH + Duration::new(secs, nanos);
+        line.frame = frame;
+        line.elapsed = elapsed;
+        line.color = color;
+        line.fields = fields;
+        line.message = message;
+        line.attachment = attachment;
+        Ok(line)
+    }
+}
+
+#[cfg(all(test, feature = "ui"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attachment_survives_a_round_trip() {
+        let line = LogLine::new(Level::Debug, "net", "packet\n".to_string())
+            .with_attachment(vec![0, 1, 0xff]);
+        let record = encode(&line);
+
+        let read = Reader(&record[4..]).line().unwrap();
+        assert_eq!(read.attachment.as_deref(), Some(&[0, 1, 0xff][..]));
+        assert_eq!(&*read.text, "packet\n");
+    }
+}
@@ -0,0 +1,20 @@
+use std::time::SystemTime;
+
+/// Source of the current time
+///
+/// The timestamps of lines, and the window's time range and rate that are based on them,
+/// are read from here so tests can swap in a clock they control.
+/// Durations such as `LoggerContext::start` and `timed` use `Instant` instead.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// The real system clock (default)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
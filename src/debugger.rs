@@ -0,0 +1,17 @@
+#[cfg(windows)]
+extern "system" {
+    fn OutputDebugStringW(output: *const u16);
+}
+
+/// Show `text` in the debugger's output pane
+#[cfg(windows)]
+pub(crate) fn write(text: &str) {
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe { OutputDebugStringW(wide.as_ptr()) }
+}
+
+/// Debuggers show stderr everywhere else
+#[cfg(not(windows))]
+pub(crate) fn write(text: &str) {
+    eprint!("{}", text);
+}
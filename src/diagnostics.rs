@@ -0,0 +1,62 @@
+use crate::LogLine;
+use log::Level;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+
+/// Target of the lines about imgui-log itself
+pub(crate) const TARGET: &str = "imgui_log";
+
+/// Color of the lines about imgui-log itself, so they can't be mistaken for the app's
+pub(crate) const COLOR: [f32; 4] = [0.75, 0.45, 1., 1.];
+
+/// Where imgui-log reports its own problems, e.g. a log file that can't be opened
+#[derive(Clone)]
+pub(crate) struct Diagnostics {
+    /// `imgui-log`, or `imgui-log[name]`
+    tag: Arc<str>,
+    /// Also shown in the window when set, see `LoggerConfig::self_log`
+    window: Option<mpsc::SyncSender<LogLine>>,
+}
+
+impl Diagnostics {
+    pub fn new(name: Option<&str>, window: Option<mpsc::SyncSender<LogLine>>) -> Self {
+        let tag = match name {
+            Some(name) => format!("imgui-log[{}]", name),
+            None => "imgui-log".to_string(),
+        };
+        Diagnostics {
+            tag: tag.into(),
+            window,
+        }
+    }
+
+    /// Write `message` to stderr, and to the window if enabled
+    pub fn report(&self, message: &str) {
+        eprintln!("{}: {}", self.tag, message);
+        if let Some(window) = &self.window {
+            // Never block, the window may be what is stuck
+            let _ = window.try_send(line(Level::Warn, message));
+        }
+    }
+
+    /// Report a failed write to `path`. Logging must not fail, so this is all a sink does
+    /// about it, and only once until writing works again, which `failing` tracks.
+    pub fn report_write(&self, failing: &AtomicBool, path: &Path, result: io::Result<()>) {
+        match result {
+            Ok(()) => failing.store(false, Ordering::Relaxed),
+            Err(e) if !failing.swap(true, Ordering::Relaxed) => {
+                self.report(&format!("could not write to {}: {}", path.display(), e));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// A line about imgui-log itself
+pub(crate) fn line(level: Level, message: &str) -> LogLine {
+    let mut line = LogLine::new(level, TARGET, format!("{}\n", message));
+    line.color = Some(COLOR);
+    line
+}
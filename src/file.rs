@@ -0,0 +1,112 @@
+use crate::diagnostics::Diagnostics;
+use crate::Severity;
+use log::{Level, LevelFilter};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Mutex;
+
+/// Roll a file over to `name.1`, `name.2`, ... once it reaches `max_bytes`
+#[derive(Clone, Copy)]
+pub(crate) struct Rotation {
+    pub max_bytes: u64,
+    pub keep: usize,
+}
+
+struct Active {
+    file: File,
+    /// Bytes in `file`, tracked so we don't have to stat it on every write
+    size: u64,
+}
+
+/// A file that formatted lines are appended to
+pub(crate) struct FileSink {
+    path: PathBuf,
+    active: Mutex<Active>,
+    filter: LevelFilter,
+    rotation: Option<Rotation>,
+    /// The last write failed, so the next failure isn't reported again
+    failing: AtomicBool,
+    diagnostics: Diagnostics,
+}
+
+impl FileSink {
+    pub fn open(
+        path: &Path,
+        filter: LevelFilter,
+        rotation: Option<Rotation>,
+        diagnostics: &Diagnostics,
+    ) -> io::Result<Self> {
+        let file = open(path)?;
+        let size = file.metadata()?.len();
+        Ok(FileSink {
+            path: path.to_owned(),
+            active: Mutex::new(Active { file, size }),
+            filter,
+            rotation,
+            failing: AtomicBool::new(false),
+            diagnostics: diagnostics.clone(),
+        })
+    }
+
+    /// True if lines of `level` belong in this file
+    pub fn admits(&self, level: Level) -> bool {
+        Severity(level).passes(self.filter)
+    }
+
+    pub fn write(&self, text: &str) {
+        let mut active = match self.active.lock() {
+            Ok(active) => active,
+            Err(_) => return,
+        };
+
+        let len = text.len() as u64;
+        if let Some(rotation) = self.rotation {
+            if active.size > 0 && active.size + len > rotation.max_bytes {
+                if let Err(e) = self.rotate(&mut active, rotation.keep) {
+                    let message = format!("could not rotate {}: {}", self.path.display(), e);
+                    self.diagnostics.report(&message);
+                }
+            }
+        }
+
+        let result = active.file.write_all(text.as_bytes());
+        if result.is_ok() {
+            active.size += len;
+        }
+        self.diagnostics
+            .report_write(&self.failing, &self.path, result);
+    }
+
+    /// Shift `name.N` to `name.N+1` (dropping the oldest), move the active file to
+    /// `name.1`, and start a fresh one
+    fn rotate(&self, active: &mut Active, keep: usize) -> io::Result<()> {
+        if keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(self.numbered(keep));
+            for n in (1..keep).rev() {
+                let from = self.numbered(n);
+                if from.exists() {
+                    fs::rename(from, self.numbered(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.numbered(1))?;
+        }
+
+        active.file = open(&self.path)?;
+        active.size = 0;
+        Ok(())
+    }
+
+    fn numbered(&self, n: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        name.into()
+    }
+}
+
+fn open(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
@@ -0,0 +1,116 @@
+use crate::{Clock, Level, LogLine, SystemClock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+
+/// The window of the installed logger, if any
+static HANDLE: Mutex<Option<LoggerHandle>> = Mutex::new(None);
+
+/// Sends lines straight to a LogWindow, bypassing the `log` facade
+///
+/// Nothing sent this way reaches stdout or other sinks.
+#[derive(Clone)]
+pub struct LoggerHandle {
+    channel: mpsc::SyncSender<LogLine>,
+    clock: Arc<dyn Clock>,
+    /// Shared with the installed logger, see `set_enabled`
+    enabled: Arc<AtomicBool>,
+}
+
+impl LoggerHandle {
+    pub fn new(channel: mpsc::SyncSender<LogLine>) -> Self {
+        LoggerHandle::with_clock(channel, Arc::new(SystemClock))
+    }
+
+    /// Timestamp toasts using `clock` rather than the system clock
+    pub fn with_clock(channel: mpsc::SyncSender<LogLine>, clock: Arc<dyn Clock>) -> Self {
+        LoggerHandle {
+            channel,
+            clock,
+            enabled: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    pub(crate) fn share_enabled(&mut self, enabled: Arc<AtomicBool>) {
+        self.enabled = enabled;
+    }
+
+    /// Turn the installed logger off or back on, e.g. to benchmark without logging overhead.
+    ///
+    /// While disabled every record is skipped before it is formatted or sent,
+    /// whatever its level, including for chained loggers. Lines pushed through
+    /// this handle still reach the window. Enabled by default.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether the installed logger is enabled, see `set_enabled`
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Add a line to the window. Returns false if it was dropped.
+    pub fn push(&self, line: LogLine) -> bool {
+        self.channel.try_send(line).is_ok()
+    }
+
+    /// Add a plain message to the window
+    pub fn toast(&self, level: Level, text: String) -> bool {
+        self.push(self.toast_line(level, text))
+    }
+
+    /// Add a plain message to the window, drawn in `color` rather than the level color
+    pub fn toast_colored(&self, level: Level, color: [f32; 4], text: String) -> bool {
+        let mut line = self.toast_line(level, text);
+        line.color = Some(color);
+        self.push(line)
+    }
+
+    fn toast_line(&self, level: Level, text: String) -> LogLine {
+        let mut line = LogLine::new(level, "imgui_log::toast", text + "\n");
+        line.timestamp = self.clock.now();
+        line
+    }
+}
+
+pub(crate) fn register(handle: LoggerHandle) {
+    *HANDLE.lock().unwrap() = Some(handle);
+}
+
+/// A handle to the window created by `init`/`init_with_config`,
+/// or to the logger installed by `init_headless`
+pub fn handle() -> Option<LoggerHandle> {
+    HANDLE.lock().unwrap().clone()
+}
+
+/// Show a message in the window only, see `toast!`
+pub fn toast(level: Level, text: String) {
+    if let Some(handle) = handle() {
+        handle.toast(level, text);
+    }
+}
+
+/// Show a message in the window only, in `color`, see `toast!`
+pub fn toast_colored(level: Level, color: [f32; 4], text: String) {
+    if let Some(handle) = handle() {
+        handle.toast_colored(level, color, text);
+    }
+}
+
+/// Show a message in the imgui window only, without going through `log`
+///
+/// Useful for user facing notifications that should not end up in the real logs.
+/// Pass a `color` to highlight it regardless of its level.
+///
+/// ```no_run
+/// imgui_log::toast!(imgui_log::Level::Info, "Saved {} files", 3);
+/// imgui_log::toast!(imgui_log::Level::Info, color: [1., 0.5, 0., 1.], "Achievement unlocked");
+/// ```
+#[macro_export]
+macro_rules! toast {
+    ($level:expr, color: $color:expr, $($arg:tt)+) => {
+        $crate::toast_colored($level, $color, format!($($arg)+))
+    };
+    ($level:expr, $($arg:tt)+) => {
+        $crate::toast($level, format!($($arg)+))
+    };
+}
@@ -0,0 +1,32 @@
+//! Hex dumps of `LogLine` attachments, see `LogLine::with_attachment`
+
+/// At most this many bytes are dumped, the window would be unusable with more
+const MAX_BYTES: usize = 4096;
+
+/// `data` as rows of 16 bytes, e.g. `0010  48 65 6c 6c 6f ...  Hello...`
+pub(crate) fn rows(data: &[u8]) -> Vec<String> {
+    let shown = &data[..data.len().min(MAX_BYTES)];
+    let mut rows: Vec<String> = shown
+        .chunks(16)
+        .enumerate()
+        .map(|(n, chunk)| {
+            let hex: Vec<String> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&b| {
+                    if b.is_ascii_graphic() || b == b' ' {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            format!("{:04x}  {:<47}  {}", n * 16, hex.join(" "), ascii)
+        })
+        .collect();
+
+    if data.len() > shown.len() {
+        rows.push(format!("... {} more bytes", data.len() - shown.len()));
+    }
+    rows
+}
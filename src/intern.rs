@@ -0,0 +1,37 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Pool of shared strings so that repeated lines/targets only store their text once
+#[derive(Default)]
+pub(crate) struct Interner {
+    pool: HashSet<Arc<str>>,
+    saved: usize,
+}
+
+impl Interner {
+    /// Returns a shared copy of `s` if an identical string was seen before
+    pub fn intern(&mut self, s: Arc<str>) -> Arc<str> {
+        if let Some(existing) = self.pool.get(&*s) {
+            self.saved += s.len();
+            existing.clone()
+        } else {
+            self.pool.insert(s.clone());
+            s
+        }
+    }
+
+    /// Forget strings that are no longer used by any line
+    pub fn prune(&mut self) {
+        self.pool.retain(|s| Arc::strong_count(s) > 1);
+    }
+
+    /// Number of distinct strings in the pool
+    pub fn len(&self) -> usize {
+        self.pool.len()
+    }
+
+    /// Total bytes that did not need to be stored thanks to interning
+    pub fn saved(&self) -> usize {
+        self.saved
+    }
+}
@@ -49,6 +49,60 @@ imgui_log::init_with_config(LoggerConfig::default()
 );
 ```
 
+# Headless usage
+
+The window can be left out entirely by disabling the default `ui` feature,
+which also drops the `imgui` dependency.
+
+```toml
+[dependencies]
+imgui-log = { version = "0.1.0", default-features = false }
+```
+
+Only the logging half is available then: `LoggerConfig`, `ChanneledLogger`,
+`LogLine`, `LogColors` and the stdout options. Start it with `init_headless`.
+
+```no_run
+imgui_log::init_headless(LoggerConfig::default().stdout_level(LevelFilter::Info));
+```
+
+# Remote viewing
+
+Enable the `net` feature to stream lines to anyone connected over TCP,
+e.g. with `nc device 9000`.
+
+```no_run
+imgui_log::init_headless(LoggerConfig::default().tcp_server("0.0.0.0:9000"));
+```
+
+# Structured fields
+
+Enable the `kv` feature to keep the key/values of a record (`log`'s `kv_unstable`).
+Lines with fields get an arrow in the window that expands them into a `key: value` list.
+
+# Binary logs
+
+Enable the `binary` feature to capture lines in a compact binary file,
+and replay it in a window later with `LogWindow::load_binary`
+(see `examples/replay.rs`).
+
+```no_run
+imgui_log::init_headless(LoggerConfig::default().binary_file("session.bin"));
+```
+
+# Tracing
+
+Enable the `tracing` feature to show `tracing` events in a window.
+`init_tracing` returns the window along with a `LogLayer` to add to your subscriber
+(see `examples/tracing.rs`). Event fields are shown like structured fields.
+
+```no_run
+use tracing_subscriber::layer::SubscriberExt;
+
+let (window, layer) = imgui_log::init_tracing(LoggerConfig::default());
+tracing::subscriber::set_global_default(tracing_subscriber::Registry::default().with(layer));
+```
+
 # Amethyst usage
 
 Enable the `amethyst-system` feature.
@@ -94,17 +148,143 @@ mod amethyst;
 #[cfg(feature = "amethyst-system")]
 pub use crate::amethyst::*;
 
-use imgui::im_str;
-use log::{Level, LevelFilter, Record};
+mod ansi;
+pub use crate::ansi::StdoutColors;
+
+#[cfg(feature = "ui")]
+mod intern;
+
+#[cfg(feature = "binary")]
+mod binary;
+#[cfg(feature = "binary")]
+use crate::binary::BinarySink;
+
+mod clock;
+pub use crate::clock::{Clock, SystemClock};
+
+mod debugger;
+
+mod diagnostics;
+use crate::diagnostics::Diagnostics;
+
+mod file;
+use crate::file::{FileSink, Rotation};
+
+mod handle;
+
+#[cfg(all(feature = "ui", feature = "attachments"))]
+mod hexdump;
+pub use crate::handle::{handle, toast, toast_colored, LoggerHandle};
+
+#[cfg(feature = "ui")]
+mod markup;
+
+#[cfg(feature = "net")]
+mod net;
+#[cfg(feature = "net")]
+use crate::net::TcpSink;
+
+mod scope;
+pub use crate::scope::{verbosity_scope, VerbosityScope};
+
+mod severity;
+pub use crate::severity::Severity;
+
+#[cfg(feature = "ui")]
+mod similar;
+
+mod timed;
+pub use crate::timed::{timed, Timed};
+
+mod timestamp;
+pub use crate::timestamp::TimestampFormat;
+
+#[cfg(feature = "tracing")]
+mod tracing;
+#[cfg(feature = "tracing")]
+pub use crate::tracing::LogLayer;
+
+#[cfg(feature = "ui")]
+mod window;
+#[cfg(feature = "ui")]
+pub use crate::window::{LogStats, LogWindow, ToolbarItem};
+
+pub use log::{Level, LevelFilter};
+
+use log::Record;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering};
 use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime};
 
 /// A single line of formatted text
 ///
 /// Call `.to_string()` if needed.
 /// level can be used to visually mark certian lines.
+/// target is the module path (or custom target) the line was logged from.
+/// bookmarked lines are marked in the window and can be jumped between.
+/// frame and elapsed are filled in when a frame source is configured (e.g. amethyst).
+/// color overrides the level color in the window, see `toast_colored`.
+/// id is unique among the lines of a LogWindow, and assigned when the window receives
+/// the line (it is 0 until then).
+/// fields are the record's structured key/values (`log::kv`), with the `kv` feature.
+/// message is the record's message without the formatter's decoration, `None` for lines
+/// that did not come from the `log` facade.
+/// attachment is binary data, shown as a hex dump under the line with the `attachments` feature.
+#[derive(Clone)]
 pub struct LogLine {
     pub level: log::Level,
-    pub text: String,
+    pub target: Arc<str>,
+    pub text: Arc<str>,
+    pub timestamp: SystemTime,
+    pub frame: Option<u64>,
+    pub elapsed: Option<f32>,
+    pub bookmarked: bool,
+    pub color: Option<[f32; 4]>,
+    pub id: u64,
+    pub fields: Vec<(String, String)>,
+    pub message: Option<Arc<str>>,
+    pub attachment: Option<Arc<[u8]>>,
+}
+
+impl LogLine {
+    /// A line that did not come from the `log` facade, e.g. for `LoggerHandle::push`.
+    ///
+    /// `text` is shown as is, no formatter is applied.
+    pub fn new(level: Level, target: &str, text: String) -> Self {
+        LogLine {
+            level,
+            target: target.into(),
+            text: text.into(),
+            timestamp: SystemTime::now(),
+            frame: None,
+            elapsed: None,
+            bookmarked: false,
+            color: None,
+            id: 0,
+            fields: vec![],
+            message: None,
+            attachment: None,
+        }
+    }
+
+    /// Attach binary data, e.g. a packet, which the window shows as a hex dump when the
+    /// line is expanded (with the `attachments` feature). Send it with `LoggerHandle::push`.
+    ///
+    /// ```no_run
+    /// # let packet = vec![0u8; 64];
+    /// use imgui_log::{Level, LogLine};
+    ///
+    /// let line = LogLine::new(Level::Debug, "net", "received packet\n".to_string());
+    /// imgui_log::handle().unwrap().push(line.with_attachment(packet));
+    /// ```
+    pub fn with_attachment(mut self, data: Vec<u8>) -> Self {
+        self.attachment = Some(data.into());
+        self
+    }
 }
 
 impl std::fmt::Display for LogLine {
@@ -113,6 +293,78 @@ impl std::fmt::Display for LogLine {
     }
 }
 
+/// What to do with a line when the window has fallen behind and the channel is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Throw the line away (default)
+    Drop,
+    /// Wait for the window to catch up.
+    ///
+    /// Only use this for levels that are never logged from the thread drawing the
+    /// window, otherwise a full channel will deadlock.
+    Block,
+}
+
+/// Lines that did not fit in the channel, shared by the logger and the window
+type Spill = Arc<SpillOver>;
+
+#[derive(Default)]
+struct SpillOver {
+    lines: Mutex<VecDeque<LogLine>>,
+    /// `lines` is not empty, so that the logger only locks it when lines have spilled over
+    pending: AtomicBool,
+}
+
+impl SpillOver {
+    /// Remove and return up to `max` of the oldest lines
+    #[cfg(feature = "ui")]
+    fn take(&self, max: usize) -> Vec<LogLine> {
+        let mut lines = match self.lines.lock() {
+            Ok(lines) => lines,
+            Err(_) => return vec![],
+        };
+        let take = lines.len().min(max);
+        let taken = lines.drain(..take).collect();
+        if lines.is_empty() {
+            self.pending.store(false, Ordering::Release);
+        }
+        taken
+    }
+}
+
+/// Inverse of `filter as u8`, for levels stored in an `AtomicU8`
+fn filter_from_u8(level: u8) -> LevelFilter {
+    match level {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Extra information passed to formatters along with the record
+pub struct LoggerContext {
+    /// When the logger was built, for measuring durations.
+    ///
+    /// This is a monotonic `Instant`, it is not read from `LoggerConfig::clock`.
+    /// Relative `TimestampFormat`s count from the clock's time instead,
+    /// so the two differ when a clock is injected.
+    pub start: Instant,
+    /// How many lines were logged before this one
+    pub count: u64,
+}
+
+/// Turns a record into the text of a line, see `LoggerConfig::formatter_with_context`
+pub type Formatter = Box<dyn Fn(&Record, &LoggerContext) -> String + Send + Sync>;
+
+/// Called with every line as it is logged, see `LoggerConfig::on_log`
+type LineCallback = Box<dyn Fn(&LogLine) + Send + Sync>;
+
+/// Returns the current frame number and elapsed seconds, see `LoggerConfig::frame_source`
+type FrameSource = fn() -> Option<(u64, f32)>;
+
 fn default_formatter(record: &Record) -> String {
     let msg = record.args().to_string();
     if let (Some(file), Some(line)) = (record.file(), record.line()) {
@@ -122,42 +374,415 @@ fn default_formatter(record: &Record) -> String {
     }
 }
 
+/// Runs `formatter`, a buggy formatter should show up in the log, not unwind through the caller
+fn run_formatter(formatter: &Formatter, record: &Record, context: &LoggerContext) -> String {
+    panic::catch_unwind(AssertUnwindSafe(|| formatter(record, context))).unwrap_or_else(|_| {
+        format!(
+            "<formatter panicked> {}: {}\n",
+            record.level(),
+            record.args()
+        )
+    })
+}
+
+/// Like `default_formatter`, but with the location padded or cut to `width` characters
+/// and the level padded, so that messages line up
+fn aligned_formatter(record: &Record, width: usize) -> String {
+    let location = if let (Some(file), Some(line)) = (record.file(), record.line()) {
+        format!("{}:{}", file, line)
+    } else {
+        record.target().to_string()
+    };
+    // Keep the end, the file name and line are more useful than the directories
+    let len = location.chars().count();
+    let location: String = location.chars().skip(len.saturating_sub(width)).collect();
+
+    format!(
+        "{:width$} {} {}\n",
+        location,
+        Severity(record.level()).name_padded(),
+        record.args(),
+        width = width
+    )
+}
+
+/// The key/values attached to `record`, as text
+#[cfg(feature = "kv")]
+fn fields(record: &Record) -> Vec<(String, String)> {
+    struct Collect(Vec<(String, String)>);
+
+    impl<'kvs> log::kv::Visitor<'kvs> for Collect {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            self.0.push((key.as_str().to_string(), value.to_string()));
+            Ok(())
+        }
+    }
+
+    let mut fields = Collect(vec![]);
+    let _ = record.key_values().visit(&mut fields);
+    fields.0
+}
+
+#[cfg(not(feature = "kv"))]
+fn fields(_: &Record) -> Vec<(String, String)> {
+    vec![]
+}
+
 /// Backend for the log crate facade
 ///
 /// Formats strings then passes them to a chaenel to be displayed in the gui,
 /// this avoids threading issues (logging must be Send+Sync).
 pub struct ChanneledLogger {
     channel: mpsc::SyncSender<LogLine>,
-    formatter: Box<dyn (Fn(&Record) -> String) + Send + Sync>,
+    /// Cleared by `LoggerHandle::set_enabled` to skip logging altogether
+    enabled: Arc<AtomicBool>,
+    /// Where our own problems are reported
+    diagnostics: Diagnostics,
+    formatter: Formatter,
+    /// Used for stdout, files, tcp and the debugger instead of `formatter` when set
+    sink_formatter: Option<Formatter>,
+    start_instant: Instant,
+    count: AtomicU64,
+    /// Lines formatted per level, indexed by `level as usize - 1`
+    level_counts: [AtomicU64; 5],
+    session_summary: bool,
+    /// Set once the session summary was written, it only happens once
+    summarized: AtomicBool,
     stdout: bool,
+    stdout_level: LevelFilter,
+    stdout_colors: Option<StdoutColors>,
+    debugger: bool,
+    timestamp: TimestampFormat,
+    frame_source: Option<FrameSource>,
+    /// Least verbose level that gets the frame prefix
+    frame_prefix_level: Level,
+    overflow: [Overflow; 5],
+    clock: Arc<dyn Clock>,
+    level: Arc<AtomicU8>,
+    /// The level may be raised at runtime, so the facade must let everything through
+    dynamic_level: bool,
+    /// Only every Nth line at this level or more verbose is kept, counted by the atomic
+    sample: Option<(Level, u64, AtomicU64)>,
+    start: SystemTime,
+    /// Seconds at which the previous line was logged, when showing deltas
+    last: Option<Mutex<Option<f64>>>,
+    files: Vec<FileSink>,
+    #[cfg(feature = "binary")]
+    binary: Option<BinarySink>,
+    #[cfg(feature = "net")]
+    tcp: Option<TcpSink>,
+    /// Set once the LogWindow has been dropped, nothing will ever read our lines again
+    disconnected: AtomicBool,
+    /// Lines thrown away because the channel was full
+    dropped: Arc<AtomicUsize>,
+    /// Where lines go while the channel is full, up to `spill_capacity` of them
+    spill: Option<Spill>,
+    spill_capacity: usize,
+    on_log: Option<LineCallback>,
+    /// Every line is also appended here, keeping at most `capture_max` of them
+    capture: Option<Arc<Mutex<Vec<LogLine>>>>,
+    capture_max: Option<usize>,
+    /// Another logger that gets every record too
+    chained: Option<Box<dyn log::Log>>,
 }
 
-impl log::Log for ChanneledLogger {
-    fn enabled(&self, metadata: &log::Metadata) -> bool {
+impl ChanneledLogger {
+    /// Create a logger that sends its lines to `channel`, configured by `config`.
+    ///
+    /// Use this to wire the backend up yourself, e.g. with your own channel
+    /// or inside another logger, rather than through `init_with_config`.
+    /// Nothing is installed, pass it to `log::set_boxed_logger` (and `log::set_max_level`)
+    /// or call it from your own `log::Log` implementation.
+    ///
+    /// ```no_run
+    /// use imgui_log::{ChanneledLogger, LogWindow, LoggerConfig};
+    /// use std::sync::mpsc;
+    ///
+    /// let (sender, receiver) = mpsc::sync_channel(1024);
+    /// let logger = ChanneledLogger::new(sender, LoggerConfig::default().stdout(false));
+    /// log::set_boxed_logger(Box::new(logger)).unwrap();
+    /// log::set_max_level(log::LevelFilter::Debug);
+    /// let window = LogWindow::new(receiver);
+    /// ```
+    pub fn new(channel: mpsc::SyncSender<LogLine>, config: LoggerConfig) -> Self {
+        config.build(channel)
+    }
+
+    /// The most verbose level currently accepted
+    pub fn level(&self) -> LevelFilter {
+        filter_from_u8(self.level.load(Ordering::Relaxed))
+    }
+
+    /// True if this logger (rather than a chained one) wants lines at `metadata`
+    fn accepts(&self, metadata: &log::Metadata) -> bool {
+        // Once the window is gone, still worth formatting if stdout, the debugger or a
+        // file is mirroring, or someone is listening
+        if self.disconnected.load(Ordering::Relaxed) {
+            let mirrored = self.stdout && Severity(metadata.level()).passes(self.stdout_level)
+                || self.debugger
+                || self.files.iter().any(|f| f.admits(metadata.level()))
+                || self.streaming()
+                || self.archiving();
+            let listened = self.on_log.is_some() || self.capture.is_some();
+            if !mirrored && !listened {
+                return false;
+            }
+        }
+
         // TODO: filter by module
-        metadata.level() <= Level::Debug
+        Severity(metadata.level()).passes(self.level())
     }
 
-    fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
-            let text = (self.formatter)(record);
+    /// Send `line` without blocking, spilling over or dropping it if the channel is full.
+    /// Returns true if the window is gone.
+    fn try_send(&self, line: LogLine) -> bool {
+        // Once lines spill over they keep doing so until the window has caught up,
+        // so that they arrive in order
+        let spilling = self.spill.as_ref();
+        let pending = spilling.is_some_and(|spill| spill.pending.load(Ordering::Acquire));
+        let line = if pending {
+            line
+        } else {
+            match self.channel.try_send(line) {
+                Ok(()) => return false,
+                Err(mpsc::TrySendError::Full(line)) => line,
+                Err(mpsc::TrySendError::Disconnected(_)) => return true,
+            }
+        };
 
-            if self.stdout {
-                // TODO: Console coloring
-                print!("{}", text);
+        let (spill, mut lines) =
+            match spilling.and_then(|spill| Some((spill, spill.lines.lock().ok()?))) {
+                Some(locked) => locked,
+                None => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return false;
+                }
+            };
+        // The window may have caught up since `pending` was read
+        let line = if pending && lines.is_empty() {
+            match self.channel.try_send(line) {
+                Ok(()) => return false,
+                Err(mpsc::TrySendError::Full(line)) => line,
+                Err(mpsc::TrySendError::Disconnected(_)) => return true,
             }
+        } else {
+            line
+        };
+        if lines.len() < self.spill_capacity {
+            lines.push_back(line);
+            spill.pending.store(true, Ordering::Release);
+        } else {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        false
+    }
+
+    /// A handle to the same window, that also switches this logger on and off
+    pub(crate) fn handle(&self) -> LoggerHandle {
+        let mut handle = LoggerHandle::with_clock(self.channel.clone(), self.clock.clone());
+        handle.share_enabled(self.enabled.clone());
+        handle
+    }
+
+    /// A line with the number of lines logged per level and how long the session lasted
+    pub fn summary(&self) -> String {
+        let count = |level: Level| self.level_counts[level as usize - 1].load(Ordering::Relaxed);
+        format!(
+            "--- session ended after {:.1}s: {} lines (error: {}, warn: {}, info: {}, debug: {}, trace: {}) ---\n",
+            self.start_instant.elapsed().as_secs_f32(),
+            self.count.load(Ordering::Relaxed),
+            count(Level::Error),
+            count(Level::Warn),
+            count(Level::Info),
+            count(Level::Debug),
+            count(Level::Trace),
+        )
+    }
+
+    /// False for the lines thrown away by `LoggerConfig::sample`
+    fn sampled(&self, level: Level) -> bool {
+        match &self.sample {
+            Some((sampled, n, count)) if level >= *sampled => {
+                count.fetch_add(1, Ordering::Relaxed) % n == 0
+            }
+            _ => true,
+        }
+    }
 
-            // TODO: File logging
+    /// True if lines are sent over the network
+    #[cfg(feature = "net")]
+    fn streaming(&self) -> bool {
+        self.tcp.is_some()
+    }
+
+    #[cfg(not(feature = "net"))]
+    fn streaming(&self) -> bool {
+        false
+    }
+
+    /// True if lines are written to a binary log
+    #[cfg(feature = "binary")]
+    fn archiving(&self) -> bool {
+        self.binary.is_some()
+    }
+
+    #[cfg(not(feature = "binary"))]
+    fn archiving(&self) -> bool {
+        false
+    }
+
+    /// Log `record`, with `fields` only collected if it is going anywhere
+    pub(crate) fn log_with_fields<F>(&self, record: &Record, fields: F)
+    where
+        F: FnOnce() -> Vec<(String, String)>,
+    {
+        // The `log` macros don't ask `enabled` first, so check again
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(chained) = &self.chained {
+            if chained.enabled(record.metadata()) {
+                chained.log(record);
+            }
+        }
+
+        if self.accepts(record.metadata()) && self.sampled(record.level()) {
+            let now = self.clock.now();
+            let frame = self.frame_source.and_then(|source| source());
+            let context = LoggerContext {
+                start: self.start_instant,
+                count: self.count.fetch_add(1, Ordering::Relaxed),
+            };
+            self.level_counts[record.level() as usize - 1].fetch_add(1, Ordering::Relaxed);
+            let shown_frame = frame.filter(|_| record.level() >= self.frame_prefix_level);
+            let mut prefix = self.timestamp.prefix(now, self.start, shown_frame);
+            if let Some(last) = &self.last {
+                // Game time when we have it, so that pausing the game doesn't show up as a stall
+                let secs = match frame {
+                    Some((_, elapsed)) => f64::from(elapsed),
+                    None => now
+                        .duration_since(self.start)
+                        .map_or(0., |d| d.as_secs_f64()),
+                };
+                let previous = last.lock().unwrap().replace(secs).unwrap_or(secs);
+                let delta = ((secs - previous) * 1000.).max(0.);
+                prefix += &format!("+{:.0}ms ", delta);
+            }
+            let text = prefix.clone() + &run_formatter(&self.formatter, record, &context);
+            // Only format a second time when the sinks were given a formatter of their own
+            let sink_text = self
+                .sink_formatter
+                .as_ref()
+                .map(|formatter| prefix + &run_formatter(formatter, record, &context));
+            let sink = sink_text.as_deref().unwrap_or(&text);
+
+            if self.stdout && Severity(record.level()).passes(self.stdout_level) {
+                if let Some(colors) = &self.stdout_colors {
+                    print!("{}", colors.paint(record.level(), sink));
+                } else {
+                    print!("{}", sink);
+                }
+            }
+
+            if self.debugger {
+                debugger::write(sink);
+            }
+            for file in self.files.iter().filter(|f| f.admits(record.level())) {
+                file.write(sink);
+            }
+            #[cfg(feature = "net")]
+            {
+                if let Some(tcp) = &self.tcp {
+                    tcp.write(sink);
+                }
+            }
 
             let line = LogLine {
-                text,
+                text: text.into(),
                 level: record.level(),
+                target: record.target().into(),
+                timestamp: now,
+                frame: frame.map(|(frame, _)| frame),
+                elapsed: frame.map(|(_, elapsed)| elapsed),
+                bookmarked: false,
+                color: None,
+                id: 0,
+                fields: fields(),
+                message: Some(record.args().to_string().into()),
+                attachment: None,
             };
-            let _ = self.channel.try_send(line);
+
+            #[cfg(feature = "binary")]
+            {
+                if let Some(binary) = &self.binary {
+                    binary.write(&line);
+                }
+            }
+
+            if let Some(on_log) = &self.on_log {
+                on_log(&line);
+            }
+            if let Some(capture) = &self.capture {
+                if let Ok(mut capture) = capture.lock() {
+                    if let Some(max) = self.capture_max {
+                        let len = capture.len();
+                        capture.drain(..(len + 1).saturating_sub(max).min(len));
+                    }
+                    capture.push(line.clone());
+                }
+            }
+
+            if self.disconnected.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let disconnected = match self.overflow[record.level() as usize - 1] {
+                Overflow::Drop => self.try_send(line),
+                Overflow::Block => self.channel.send(line).is_err(),
+            };
+            if disconnected && !self.disconnected.swap(true, Ordering::Relaxed) {
+                self.diagnostics
+                    .report("LogWindow was dropped, no longer capturing logs");
+            }
         }
     }
+}
+
+impl log::Log for ChanneledLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return false;
+        }
+        self.accepts(metadata)
+            || self
+                .chained
+                .as_ref()
+                .is_some_and(|chained| chained.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        self.log_with_fields(record, || fields(record));
+    }
+
+    fn flush(&self) {
+        if self.session_summary && !self.summarized.swap(true, Ordering::Relaxed) {
+            let summary = self.summary();
+            if self.stdout {
+                print!("{}", summary);
+            }
+            for file in &self.files {
+                file.write(&summary);
+            }
+        }
 
-    fn flush(&self) {}
+        if let Some(chained) = &self.chained {
+            chained.flush();
+        }
+    }
 }
 
 /// Colors used by LogWindow when rendering
@@ -183,6 +808,21 @@ impl Default for LogColors {
 }
 
 impl LogColors {
+    /// A palette that stays distinguishable with red-green color blindness,
+    /// based on the Okabe-Ito colors.
+    ///
+    /// Consider `LogWindow::set_level_tags` or `set_severity_stripe` as well,
+    /// so the level isn't conveyed by color alone.
+    pub fn colorblind_safe() -> Self {
+        LogColors {
+            trace: [0.6, 0.6, 0.6, 1.],
+            debug: [0.34, 0.71, 0.91, 1.],
+            info: [1., 1., 1., 1.],
+            warn: [0.94, 0.89, 0.26, 1.],
+            error: [0.84, 0.37, 0., 1.],
+        }
+    }
+
     pub fn level(&self, level: Level) -> [f32; 4] {
         match level {
             Level::Trace => self.trace,
@@ -194,117 +834,196 @@ impl LogColors {
     }
 }
 
-/// The imgui frontend for ChanneledLogger.
-/// Call `build` during your rendering stage
-pub struct LogWindow {
-    buf: Vec<LogLine>,
-    channel: mpsc::Receiver<LogLine>,
-    autoscroll: bool,
-    colors: LogColors,
-}
-
-impl LogWindow {
-    pub fn new(channel: mpsc::Receiver<LogLine>) -> Self {
-        LogWindow {
-            buf: vec![],
-            channel,
-            autoscroll: false,
-            colors: LogColors::default(),
-        }
-    }
-}
-
-impl LogWindow {
-    fn sync(&mut self) {
-        while let Ok(line) = self.channel.try_recv() {
-            self.buf.push(line);
-        }
-    }
-
-    pub fn clear(&mut self) {
-        self.buf.clear();
-    }
-
-    pub fn set_colors(&mut self, colors: LogColors) {
-        self.colors = colors;
-    }
-
-    pub fn build(&mut self, ui: &imgui::Ui, window: imgui::Window) {
-        self.sync();
-        window.build(ui, || {
-            ui.popup(im_str!("Options"), || {
-                ui.checkbox(im_str!("Auto-scroll"), &mut self.autoscroll);
-            });
-
-            if ui.button(im_str!("Options"), [0., 0.]) {
-                ui.open_popup(im_str!("Options"));
-            }
-            ui.same_line(0.);
-            let clear = ui.button(im_str!("Clear"), [0., 0.]);
-            ui.same_line(0.);
-            let copy = ui.button(im_str!("Copy"), [0., 0.]);
-
-            ui.separator();
-            let child = imgui::ChildWindow::new(imgui::Id::Str("scrolling"))
-                .size([0., 0.])
-                .horizontal_scrollbar(true);
-            child.build(ui, || {
-                if clear {
-                    self.clear();
-                }
-                let buf = &mut self.buf;
-                if copy {
-                    ui.set_clipboard_text(&imgui::ImString::new(
-                        buf.iter()
-                            .map(|l| l.to_string())
-                            .collect::<Vec<String>>()
-                            .join("\n"),
-                    ));
-                }
-
-                let style = ui.push_style_var(imgui::StyleVar::ItemSpacing([0., 0.]));
-
-                for record in buf {
-                    ui.text_colored(self.colors.level(record.level), &record.text);
-                }
-
-                style.pop(ui);
-
-                if self.autoscroll || ui.scroll_y() >= ui.scroll_max_y() {
-                    ui.set_scroll_here_y_with_ratio(1.0);
-                }
-            });
-        });
-    }
-}
-
 /// ChanneledLogger builder
 ///
 /// Use `LoggerConfig::default()` to intialize.
 ///
 /// Call `.build()` to finalize.
 pub struct LoggerConfig {
-    formatter: Option<Box<dyn (Fn(&Record) -> String) + Send + Sync>>,
+    formatter: Option<Formatter>,
+    sink_formatter: Option<Formatter>,
+    #[cfg(feature = "ui")]
     colors: Option<LogColors>,
     stdout: bool,
+    stdout_level: LevelFilter,
+    stdout_colors: Option<StdoutColors>,
+    debugger: bool,
+    timestamp: TimestampFormat,
+    delta: bool,
+    session_summary: bool,
+    frame_source: Option<FrameSource>,
+    frame_prefix_level: Level,
+    overflow: [Overflow; 5],
+    clock: Arc<dyn Clock>,
+    max_level: LevelFilter,
+    level: Option<Arc<AtomicU8>>,
+    sample: Option<(Level, u64)>,
+    files: Vec<(PathBuf, LevelFilter)>,
+    #[cfg(feature = "binary")]
+    binary: Option<PathBuf>,
+    rotation: Option<Rotation>,
+    #[cfg(feature = "net")]
+    tcp: Option<Vec<std::net::SocketAddr>>,
+    on_log: Option<LineCallback>,
+    /// Every line is also appended here, keeping at most `capture_max` of them
+    capture: Option<Arc<Mutex<Vec<LogLine>>>>,
+    capture_max: Option<usize>,
+    chained: Option<Box<dyn log::Log>>,
+    #[cfg(feature = "ui")]
+    install_global: bool,
+    name: Option<String>,
+    self_log: bool,
+    /// Reported once the logger is built, see `self_log`
+    problems: Vec<String>,
+    spill_capacity: usize,
+    #[cfg(feature = "amethyst-system")]
+    start_open: bool,
+    #[cfg(feature = "amethyst-system")]
+    window_position: Option<[f32; 2]>,
+    #[cfg(feature = "amethyst-system")]
+    window_size: Option<[f32; 2]>,
 }
 
 impl Default for LoggerConfig {
     fn default() -> Self {
         LoggerConfig {
             formatter: None,
+            sink_formatter: None,
+            #[cfg(feature = "ui")]
             colors: None,
             stdout: true,
+            stdout_level: LevelFilter::Trace,
+            stdout_colors: None,
+            debugger: false,
+            timestamp: TimestampFormat::None,
+            delta: false,
+            session_summary: false,
+            frame_source: None,
+            frame_prefix_level: Level::Error,
+            overflow: [Overflow::Drop; 5],
+            clock: Arc::new(SystemClock),
+            max_level: LevelFilter::Debug,
+            level: None,
+            sample: None,
+            files: vec![],
+            #[cfg(feature = "binary")]
+            binary: None,
+            rotation: None,
+            #[cfg(feature = "net")]
+            tcp: None,
+            on_log: None,
+            capture: None,
+            capture_max: None,
+            chained: None,
+            #[cfg(feature = "ui")]
+            install_global: true,
+            name: None,
+            self_log: false,
+            problems: vec![],
+            spill_capacity: 0,
+            #[cfg(feature = "amethyst-system")]
+            start_open: true,
+            #[cfg(feature = "amethyst-system")]
+            window_position: None,
+            #[cfg(feature = "amethyst-system")]
+            window_size: None,
         }
     }
 }
 
 impl LoggerConfig {
+    /// Name this logger, to tell several apart.
+    ///
+    /// The window created by `init_with_config` shows it in its title (with amethyst)
+    /// and it is included in our messages on stderr, e.g. `imgui-log[net]: ...`.
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Whether the window of `create_system_with_config` starts open, true by default.
+    ///
+    /// It can be opened later with `LogSystem::set_open` or `set_auto_open_on_error`.
+    #[cfg(feature = "amethyst-system")]
+    pub fn start_open(mut self, start_open: bool) -> Self {
+        self.start_open = start_open;
+        self
+    }
+
+    /// Where the window of `create_system_with_config` first appears, in pixels
+    #[cfg(feature = "amethyst-system")]
+    pub fn window_position(mut self, position: [f32; 2]) -> Self {
+        self.window_position = Some(position);
+        self
+    }
+
+    /// The size the window of `create_system_with_config` first appears with, in pixels
+    #[cfg(feature = "amethyst-system")]
+    pub fn window_size(mut self, size: [f32; 2]) -> Self {
+        self.window_size = Some(size);
+        self
+    }
+
+    /// Also show imgui-log's own problems in the window, e.g. a log file that can't be
+    /// written to, rather than only on stderr. They are logged with the target
+    /// `imgui_log` and drawn in a color of their own.
+    pub fn self_log(mut self, self_log: bool) -> Self {
+        self.self_log = self_log;
+        self
+    }
+
+    /// Format lines in the window, and in the sinks unless `sink_formatter` is set.
     pub fn formatter(mut self, formatter: fn(&Record) -> String) -> Self {
+        self.formatter = Some(Box::new(move |record, _| formatter(record)));
+        self
+    }
+
+    /// Use a formatter that lines messages up in a column.
+    ///
+    /// The location is padded, or cut from the start, to `location_width` characters,
+    /// followed by the level padded to 5, e.g. `src/main.rs:42 INFO  Hello`.
+    pub fn aligned_format(self, location_width: usize) -> Self {
+        self.formatter_with_context(move |record, _| aligned_formatter(record, location_width))
+    }
+
+    /// Like `formatter`, but also given a `LoggerContext`,
+    /// e.g. to show the time since startup without a frame source.
+    ///
+    /// ```no_run
+    /// # use imgui_log::LoggerConfig;
+    /// LoggerConfig::default().formatter_with_context(|record, context| {
+    ///     format!("{:.3} {}\n", context.start.elapsed().as_secs_f32(), record.args())
+    /// });
+    /// ```
+    pub fn formatter_with_context<F>(mut self, formatter: F) -> Self
+    where
+        F: Fn(&Record, &LoggerContext) -> String + Send + Sync + 'static,
+    {
         self.formatter = Some(Box::new(formatter));
         self
     }
 
+    /// Same as `formatter_with_context`, named to pair with `sink_formatter`.
+    pub fn window_formatter<F>(self, formatter: F) -> Self
+    where
+        F: Fn(&Record, &LoggerContext) -> String + Send + Sync + 'static,
+    {
+        self.formatter_with_context(formatter)
+    }
+
+    /// Format lines differently for stdout, files, tcp and the debugger than for the window,
+    /// e.g. to write plain lines to a file while the window shows a short form.
+    ///
+    /// When unset, the sinks get the window's text. Prefixes such as `timestamp` apply to both.
+    pub fn sink_formatter<F>(mut self, formatter: F) -> Self
+    where
+        F: Fn(&Record, &LoggerContext) -> String + Send + Sync + 'static,
+    {
+        self.sink_formatter = Some(Box::new(formatter));
+        self
+    }
+
+    #[cfg(feature = "ui")]
     pub fn colors(mut self, colors: LogColors) -> Self {
         self.colors = Some(colors);
         self
@@ -315,19 +1034,311 @@ impl LoggerConfig {
         self
     }
 
+    /// Only mirror lines at or above this level to stdout.
+    ///
+    /// Lines are still sent to the window. Defaults to everything the logger accepts.
+    pub fn stdout_level(mut self, level: LevelFilter) -> Self {
+        self.stdout_level = level;
+        self
+    }
+
+    /// Color stdout output using ANSI escape codes.
+    ///
+    /// This palette is independent of the window colors,
+    /// use `StdoutColors::from(colors)` to approximate them.
+    pub fn stdout_colors(mut self, colors: StdoutColors) -> Self {
+        self.stdout_colors = Some(colors);
+        self
+    }
+
+    /// Write a summary line when the logger is first flushed,
+    /// e.g. `--- session ended after 61.2s: 1234 lines (error: 2, warn: 5, ...) ---`,
+    /// to stdout and the log files. Call `log::logger().flush()` when shutting down.
+    pub fn session_summary(mut self, session_summary: bool) -> Self {
+        self.session_summary = session_summary;
+        self
+    }
+
+    /// Also write every line to the debugger's output pane (`OutputDebugStringW`) on Windows,
+    /// or to stderr elsewhere. Off by default.
+    pub fn debugger_output(mut self, debugger: bool) -> Self {
+        self.debugger = debugger;
+        self
+    }
+
+    /// Prefix every line with a timestamp, regardless of the formatter used.
+    ///
+    /// The frame prefix (see `frame_source`) follows it, in the same style.
+    pub fn timestamp_format(mut self, timestamp: TimestampFormat) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Prefix every line with the time since the previous one, e.g. `+16ms`, to spot stalls.
+    ///
+    /// Uses the elapsed time from the frame source when there is one.
+    pub fn delta_time(mut self, delta: bool) -> Self {
+        self.delta = delta;
+        self
+    }
+
+    /// Where to read the current `(frame, seconds elapsed)` from when a line is logged.
+    ///
+    /// These are stored on each `LogLine` and shown as a `[00042][1.2s]` prefix.
+    /// The amethyst system sets this up automatically.
+    pub fn frame_source(mut self, source: FrameSource) -> Self {
+        self.frame_source = Some(source);
+        self
+    }
+
+    /// Only show the frame prefix on lines at `level` or more verbose,
+    /// e.g. `Level::Debug` to keep info messages short. Every level has it by default.
+    ///
+    /// The frame is still stored on every `LogLine`.
+    pub fn frame_prefix_from(mut self, level: Level) -> Self {
+        self.frame_prefix_level = level;
+        self
+    }
+
+    /// What to do with lines of `level` when the window falls behind
+    pub fn on_full_for(mut self, level: Level, overflow: Overflow) -> Self {
+        self.overflow[level as usize - 1] = overflow;
+        self
+    }
+
+    /// Keep up to `capacity` lines that don't fit in the channel in a second buffer,
+    /// rather than dropping them right away, for extra headroom during bursts.
+    ///
+    /// Only lines of levels set to `Overflow::Drop` spill over. The window takes them
+    /// after the channel. Lines are still dropped once this buffer is full too.
+    pub fn spill_capacity(mut self, capacity: usize) -> Self {
+        self.spill_capacity = capacity;
+        self
+    }
+
+    /// Append every line to the file at `path`, without colors
+    pub fn file<P: AsRef<Path>>(self, path: P) -> Self {
+        self.file_filtered(path, LevelFilter::Trace)
+    }
+
+    /// Append lines at or above `level` to the file at `path`.
+    ///
+    /// Can be called repeatedly to write to several files,
+    /// e.g. everything to `full.log` and only errors to `errors.log`.
+    /// Files that cannot be opened are reported on stderr and skipped.
+    pub fn file_filtered<P: AsRef<Path>>(mut self, path: P, level: LevelFilter) -> Self {
+        self.files.push((path.as_ref().to_owned(), level));
+        self
+    }
+
+    /// Append every line to the file at `path` in a compact binary format,
+    /// which is smaller and cheaper to write than text at high log rates.
+    ///
+    /// Read it back with `LogWindow::load_binary`. Not affected by `file_rotation`.
+    #[cfg(feature = "binary")]
+    pub fn binary_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.binary = Some(path.as_ref().to_owned());
+        self
+    }
+
+    /// Roll every log file over once it would grow past `max_bytes`.
+    ///
+    /// The full file is renamed to `name.1`, the previous `name.1` to `name.2`, and so on,
+    /// keeping at most `keep` old files. The oldest one is deleted.
+    pub fn file_rotation(mut self, max_bytes: u64, keep: usize) -> Self {
+        self.rotation = Some(Rotation { max_bytes, keep });
+        self
+    }
+
+    /// Only log lines at or above `level`. Defaults to `Debug`.
+    ///
+    /// Anything below it is skipped by the `log` macros without being formatted.
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.max_level = level;
+        self
+    }
+
+    /// Only keep every `n`th line at `level` or more verbose, e.g. `sample(Level::Trace, 100)`
+    /// to bound the cost of a flood of trace lines. The others are thrown away before formatting.
+    ///
+    /// This loses lines by design, which ones depends only on how many came before them.
+    /// It does not limit how many lines are logged per second.
+    pub fn sample(mut self, level: Level, n: u64) -> Self {
+        self.sample = Some((level, n.max(1)));
+        self
+    }
+
+    /// Stream every line to anyone connected to `addr`, e.g. to watch a headless device.
+    ///
+    /// Connections are accepted on a background thread and each line is sent newline
+    /// terminated, as formatted and without colors. A client that can't keep up
+    /// misses lines rather than slowing down logging.
+    #[cfg(feature = "net")]
+    pub fn tcp_server<A: std::net::ToSocketAddrs>(mut self, addr: A) -> Self {
+        match addr.to_socket_addrs() {
+            Ok(addrs) => self.tcp = Some(addrs.collect()),
+            Err(e) => self
+                .problems
+                .push(format!("invalid tcp server address: {}", e)),
+        }
+        self
+    }
+
+    /// Read the maximum level from `level` every time something is logged.
+    ///
+    /// Store a `LevelFilter as u8` into it at any time to change the verbosity,
+    /// e.g. from a debug menu elsewhere in your game.
+    pub fn shared_level(mut self, level: Arc<AtomicU8>) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Read the time from `clock` instead of the system clock, e.g. to control it in tests
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Call `f` with every line as it is logged.
+    ///
+    /// This runs on whichever thread emitted the log, before the line reaches
+    /// the window, and keeps firing even if the window is never drawn.
+    pub fn on_log<F: Fn(&LogLine) + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.on_log = Some(Box::new(f));
+        self
+    }
+
+    /// Also append every line to `buffer`, e.g. to assert on what was logged in tests
+    /// or to analyze it from other code, without a window or draining a channel.
+    ///
+    /// Lock it to read the lines at any time. See `capture_max_lines` to bound it.
+    pub fn capture_buffer(mut self, buffer: Arc<Mutex<Vec<LogLine>>>) -> Self {
+        self.capture = Some(buffer);
+        self
+    }
+
+    /// Only keep the newest `max` lines in the `capture_buffer`
+    pub fn capture_max_lines(mut self, max: usize) -> Self {
+        self.capture_max = Some(max.max(1));
+        self
+    }
+
+    /// Pass every record on to `logger` as well, e.g. an `env_logger` you already use.
+    ///
+    /// Only one logger can be installed with `log`, this lets imgui-log be that
+    /// logger while the other keeps working. `logger` does its own filtering.
+    pub fn chain(mut self, logger: Box<dyn log::Log>) -> Self {
+        self.chained = Some(logger);
+        self
+    }
+
+    /// Whether `init_with_config` installs the logger for the `log` macros. Defaults to true.
+    ///
+    /// Turn this off to use the window purely as a console fed through `handle()`,
+    /// leaving the global logger free for something else.
+    #[cfg(feature = "ui")]
+    pub fn install_global(mut self, install_global: bool) -> Self {
+        self.install_global = install_global;
+        self
+    }
+
+    /// Create the logger, same as `ChanneledLogger::new`
     pub fn build(self, channel: mpsc::SyncSender<LogLine>) -> ChanneledLogger {
+        let window = if self.self_log {
+            Some(channel.clone())
+        } else {
+            None
+        };
+        let diagnostics = Diagnostics::new(self.name.as_deref(), window);
+        for problem in &self.problems {
+            diagnostics.report(problem);
+        }
         let formatter = {
             if let Some(f) = self.formatter {
                 f
             } else {
-                Box::new(default_formatter)
+                Box::new(|record: &Record, _: &LoggerContext| default_formatter(record))
             }
         };
 
+        let max_level = self.max_level;
+        let rotation = self.rotation;
+        let files = self
+            .files
+            .iter()
+            .filter_map(|(path, level)| {
+                match FileSink::open(path, *level, rotation, &diagnostics) {
+                    Ok(file) => Some(file),
+                    Err(e) => {
+                        diagnostics.report(&format!("could not open {}: {}", path.display(), e));
+                        None
+                    }
+                }
+            })
+            .collect();
+
         ChanneledLogger {
             channel,
+            enabled: Arc::new(AtomicBool::new(true)),
             formatter,
+            sink_formatter: self.sink_formatter,
+            start_instant: Instant::now(),
+            count: AtomicU64::new(0),
+            level_counts: Default::default(),
+            session_summary: self.session_summary,
+            summarized: AtomicBool::new(false),
             stdout: self.stdout,
+            stdout_level: self.stdout_level,
+            stdout_colors: self.stdout_colors,
+            debugger: self.debugger,
+            timestamp: self.timestamp,
+            frame_source: self.frame_source,
+            frame_prefix_level: self.frame_prefix_level,
+            overflow: self.overflow,
+            start: self.clock.now(),
+            last: if self.delta {
+                Some(Mutex::new(None))
+            } else {
+                None
+            },
+            clock: self.clock,
+            dynamic_level: self.level.is_some(),
+            sample: self.sample.map(|(level, n)| (level, n, AtomicU64::new(0))),
+            level: self
+                .level
+                .unwrap_or_else(|| Arc::new(AtomicU8::new(max_level as u8))),
+            files,
+            #[cfg(feature = "binary")]
+            binary: self
+                .binary
+                .and_then(|path| match BinarySink::open(&path, &diagnostics) {
+                    Ok(binary) => Some(binary),
+                    Err(e) => {
+                        diagnostics.report(&format!("could not open {}: {}", path.display(), e));
+                        None
+                    }
+                }),
+            #[cfg(feature = "net")]
+            tcp: self.tcp.and_then(|addrs| match TcpSink::bind(&addrs) {
+                Ok(tcp) => Some(tcp),
+                Err(e) => {
+                    diagnostics.report(&format!("could not start tcp server: {}", e));
+                    None
+                }
+            }),
+            disconnected: AtomicBool::new(false),
+            dropped: Arc::new(AtomicUsize::new(0)),
+            spill: if self.spill_capacity > 0 {
+                Some(Arc::new(SpillOver::default()))
+            } else {
+                None
+            },
+            spill_capacity: self.spill_capacity,
+            on_log: self.on_log,
+            capture: self.capture,
+            capture_max: self.capture_max,
+            chained: self.chained,
+            diagnostics,
         }
     }
 }
@@ -335,27 +1346,244 @@ impl LoggerConfig {
 /// Hook into the log system.
 /// This consumes the ChanneledLogger. Edit any configurations before this.
 fn set_logger(logger: ChanneledLogger) -> Result<(), log::SetLoggerError> {
-    log::set_boxed_logger(Box::new(logger)).map(|()| log::set_max_level(LevelFilter::Debug))
+    // The facade filters before we ever see a line, so it has to match our own level.
+    // A chained logger may want more, it filters for itself.
+    let max_level = if logger.dynamic_level || logger.chained.is_some() {
+        LevelFilter::Trace
+    } else {
+        logger.level()
+    };
+    let level = logger.level.clone();
+    log::set_boxed_logger(Box::new(logger)).map(|()| {
+        log::set_max_level(max_level);
+        scope::register(level);
+    })
 }
 
 /// Create a window and initialize the logging backend.
 /// Be sure to call build on the returned window during your rendering stage
+#[cfg(feature = "ui")]
 pub fn init_with_config(config: LoggerConfig) -> LogWindow {
     let (log_writer, log_reader) = mpsc::sync_channel(128);
+    let mut window = new_window(&config, log_reader);
+
+    if !config.install_global {
+        handle::register(LoggerHandle::with_clock(
+            log_writer.clone(),
+            config.clock.clone(),
+        ));
+        return window;
+    }
+
+    let logger = connect(&mut window, config, log_writer);
+    handle::register(logger.handle());
+    set_logger(logger).unwrap();
+
+    window
+}
+
+/// Create a window and a layer that logs `tracing` events to it, see `LogLayer`.
+///
+/// Nothing is installed, add the layer to your subscriber. To also show `log` records,
+/// call `init_with_config` separately: each call creates its own window.
+///
+/// `handle()` and `toast!` keep going to the `init_with_config` window,
+/// use `LogLayer::handle` for this one.
+#[cfg(all(feature = "ui", feature = "tracing"))]
+pub fn init_tracing(config: LoggerConfig) -> (LogWindow, LogLayer) {
+    let (log_writer, log_reader) = mpsc::sync_channel(128);
+    let mut window = new_window(&config, log_reader);
+    let logger = connect(&mut window, config, log_writer);
+    (window, LogLayer::new(logger))
+}
 
+#[cfg(feature = "ui")]
+fn new_window(config: &LoggerConfig, log_reader: mpsc::Receiver<LogLine>) -> LogWindow {
     let mut window = LogWindow::new(log_reader);
+    window.set_clock(config.clock.clone());
     if let Some(colors) = config.colors {
         window.set_colors(colors);
     }
+    if let Some(name) = &config.name {
+        window.set_name(name.clone());
+    }
+    window.set_self_log(config.self_log);
+    window
+}
 
+/// Build the logger writing to `window`
+#[cfg(feature = "ui")]
+fn connect(
+    window: &mut LogWindow,
+    config: LoggerConfig,
+    log_writer: mpsc::SyncSender<LogLine>,
+) -> ChanneledLogger {
     let logger = config.build(log_writer);
-    set_logger(logger).unwrap();
-
-    window
+    window.set_dropped_counter(logger.dropped.clone());
+    if let Some(spill) = &logger.spill {
+        window.set_spill(spill.clone());
+    }
+    window.set_level_source(logger.level.clone());
+    logger
 }
 
 /// Create a window and initialize the logging backend with the default config.
 /// Be sure to call build on the returned window during your rendering stage
+#[cfg(feature = "ui")]
 pub fn init() -> LogWindow {
     init_with_config(LoggerConfig::default())
 }
+
+/// Initialize the logging backend without a window.
+///
+/// Lines are still mirrored to stdout and passed to `LoggerConfig::on_log`,
+/// which makes this usable in headless builds without the `ui` feature.
+/// `handle()` can still switch it off with `LoggerHandle::set_enabled`.
+pub fn init_headless(config: LoggerConfig) {
+    // Nothing will ever receive from this channel
+    let (log_writer, _) = mpsc::sync_channel(0);
+    let logger = config.build(log_writer);
+    logger.disconnected.store(true, Ordering::Relaxed);
+    handle::register(logger.handle());
+    set_logger(logger).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Log;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn log(logger: &ChanneledLogger, message: &str) {
+        logger.log(
+            &Record::builder()
+                .level(Level::Info)
+                .target("test")
+                .args(format_args!("{}", message))
+                .build(),
+        );
+    }
+
+    /// A clock that only moves when told to
+    struct TestClock(Mutex<SystemTime>);
+
+    impl Clock for TestClock {
+        fn now(&self) -> SystemTime {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn captured(capture: &Arc<Mutex<Vec<LogLine>>>) -> Vec<String> {
+        let capture = capture.lock().unwrap();
+        capture.iter().map(|line| line.text.to_string()).collect()
+    }
+
+    #[test]
+    fn panicking_formatter_falls_back() {
+        let (sender, _receiver) = mpsc::sync_channel(16);
+        let capture = Arc::new(Mutex::new(vec![]));
+        let config = LoggerConfig::default()
+            .stdout(false)
+            .formatter_with_context(|_, _| panic!("broken formatter"))
+            .capture_buffer(capture.clone());
+        let logger = ChanneledLogger::new(sender, config);
+
+        log(&logger, "hello");
+        assert_eq!(captured(&capture), ["<formatter panicked> INFO: hello\n"]);
+    }
+
+    #[test]
+    fn timestamps_come_from_the_clock() {
+        let start = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let clock = Arc::new(TestClock(Mutex::new(start)));
+        let (sender, receiver) = mpsc::sync_channel(16);
+        let config = LoggerConfig::default()
+            .stdout(false)
+            .formatter(|record| format!("{}", record.args()))
+            .timestamp_format(TimestampFormat::RelativeSecs)
+            .clock(clock.clone());
+        let logger = ChanneledLogger::new(sender, config);
+
+        *clock.0.lock().unwrap() += Duration::from_millis(2500);
+        log(&logger, "later");
+        let line = receiver.try_recv().unwrap();
+        assert_eq!(&*line.text, "[2.5s] later");
+        assert_eq!(line.timestamp, start + Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn capture_keeps_the_newest_lines() {
+        let (sender, _receiver) = mpsc::sync_channel(16);
+        let capture = Arc::new(Mutex::new(vec![]));
+        let config = LoggerConfig::default()
+            .stdout(false)
+            .formatter(|record| format!("{}", record.args()))
+            .capture_buffer(capture.clone())
+            .capture_max_lines(2);
+        let logger = ChanneledLogger::new(sender, config);
+
+        for message in &["one", "two", "three"] {
+            log(&logger, message);
+        }
+        assert_eq!(captured(&capture), ["two", "three"]);
+    }
+
+    #[test]
+    #[cfg(feature = "ui")]
+    fn spilled_lines_keep_their_order() {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let config = LoggerConfig::default()
+            .stdout(false)
+            .formatter(|record| format!("{}", record.args()))
+            .spill_capacity(4);
+        let logger = ChanneledLogger::new(sender, config);
+        let spill = logger.spill.clone().unwrap();
+
+        for message in &["one", "two", "three"] {
+            log(&logger, message);
+        }
+        // The channel has room again, but "four" must still come after "three"
+        assert_eq!(&*receiver.try_recv().unwrap().text, "one");
+        log(&logger, "four");
+        assert!(receiver.try_recv().is_err());
+
+        let texts: Vec<_> = spill
+            .take(usize::MAX)
+            .iter()
+            .map(|l| l.text.to_string())
+            .collect();
+        assert_eq!(texts, ["two", "three", "four"]);
+        log(&logger, "five");
+        assert_eq!(&*receiver.try_recv().unwrap().text, "five");
+    }
+
+    #[test]
+    fn disabled_logger_skips_lines() {
+        let (sender, receiver) = mpsc::sync_channel(16);
+        let logger = ChanneledLogger::new(sender, LoggerConfig::default().stdout(false));
+        let handle = logger.handle();
+
+        handle.set_enabled(false);
+        log(&logger, "skipped");
+        assert!(receiver.try_recv().is_err());
+
+        handle.set_enabled(true);
+        log(&logger, "kept");
+        assert!(receiver.try_recv().is_ok());
+    }
+
+    #[test]
+    fn logs_to_a_user_channel() {
+        let (sender, receiver) = mpsc::sync_channel(16);
+        let config = LoggerConfig::default()
+            .stdout(false)
+            .formatter(|record| format!("{}", record.args()));
+        let logger = ChanneledLogger::new(sender, config);
+
+        log(&logger, "hello");
+        let line = receiver.try_recv().unwrap();
+        assert_eq!(&*line.text, "hello");
+        assert_eq!(line.level, Level::Info);
+        assert_eq!(&*line.target, "test");
+    }
+}
@@ -0,0 +1,94 @@
+//! Inline color markup for log messages
+//!
+//! `{color:red}some text{/}` renders "some text" in red.
+//! Colors can be a name (`red`, `green`, `blue`, `yellow`, `cyan`, `magenta`,
+//! `orange`, `white`, `gray`, `black`), a level name (`error`, `warn`, `info`,
+//! `debug`, `trace`) which uses the window's `LogColors`, or a hex `#rrggbb`.
+//! Tags do not nest. Anything that isn't a complete, valid tag is shown as is.
+
+use crate::LogColors;
+
+const OPEN: &str = "{color:";
+const CLOSE: &str = "{/}";
+
+/// A run of text and the color it should be drawn with (`None` for the line color)
+pub(crate) type Segment<'a> = (Option<[f32; 4]>, &'a str);
+
+/// Split `text` into colored segments
+pub(crate) fn parse<'a>(text: &'a str, colors: &LogColors) -> Vec<Segment<'a>> {
+    let mut segments = vec![];
+    let mut rest = text;
+    let mut offset = 0;
+
+    while let Some(found) = rest[offset..].find(OPEN) {
+        let tag_start = offset + found;
+        if let Some((color, body, len)) = parse_tag(&rest[tag_start..], colors) {
+            if tag_start > 0 {
+                segments.push((None, &rest[..tag_start]));
+            }
+            if !body.is_empty() {
+                segments.push((Some(color), body));
+            }
+            rest = &rest[tag_start + len..];
+            offset = 0;
+        } else {
+            // Not a valid tag, keep it as text and look further along
+            offset = tag_start + OPEN.len();
+        }
+    }
+
+    if !rest.is_empty() {
+        segments.push((None, rest));
+    }
+    segments
+}
+
+/// The text with all valid tags removed
+pub(crate) fn strip(text: &str, colors: &LogColors) -> String {
+    parse(text, colors).into_iter().map(|(_, s)| s).collect()
+}
+
+/// Parse a tag at the start of `s`, returning its color, body and total length
+fn parse_tag<'a>(s: &'a str, colors: &LogColors) -> Option<([f32; 4], &'a str, usize)> {
+    let name_end = s.find('}')?;
+    let color = parse_color(&s[OPEN.len()..name_end], colors)?;
+    let body_start = name_end + 1;
+    let body_len = s[body_start..].find(CLOSE)?;
+    let body = &s[body_start..body_start + body_len];
+    Some((color, body, body_start + body_len + CLOSE.len()))
+}
+
+fn parse_color(name: &str, colors: &LogColors) -> Option<[f32; 4]> {
+    let color = match name {
+        "red" => [1., 0., 0., 1.],
+        "green" => [0., 1., 0., 1.],
+        "blue" => [0., 0., 1., 1.],
+        "yellow" => [1., 1., 0., 1.],
+        "cyan" => [0., 1., 1., 1.],
+        "magenta" => [1., 0., 1., 1.],
+        "orange" => [1., 0.5, 0., 1.],
+        "white" => [1., 1., 1., 1.],
+        "gray" => [0.5, 0.5, 0.5, 1.],
+        "black" => [0., 0., 0., 1.],
+        "error" => colors.error,
+        "warn" => colors.warn,
+        "info" => colors.info,
+        "debug" => colors.debug,
+        "trace" => colors.trace,
+        hex => return parse_hex(hex),
+    };
+    Some(color)
+}
+
+fn parse_hex(hex: &str) -> Option<[f32; 4]> {
+    let hex = hex.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| {
+        u8::from_str_radix(hex.get(i..i + 2)?, 16)
+            .ok()
+            .map(|c| f32::from(c) / 255.)
+    };
+    Some([channel(0)?, channel(2)?, channel(4)?, 1.])
+}
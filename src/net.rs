@@ -0,0 +1,53 @@
+use std::io::{self, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+/// Lines buffered for each client before new ones are dropped for it
+const CLIENT_BACKLOG: usize = 256;
+
+/// Streams formatted lines to every client connected to a TCP port
+pub(crate) struct TcpSink {
+    clients: Arc<Mutex<Vec<mpsc::SyncSender<Arc<str>>>>>,
+}
+
+impl TcpSink {
+    /// Start accepting clients on a background thread
+    pub fn bind(addrs: &[SocketAddr]) -> io::Result<Self> {
+        let listener = TcpListener::bind(addrs)?;
+        let clients = Arc::new(Mutex::new(vec![]));
+
+        let accepted = clients.clone();
+        thread::Builder::new()
+            .name("imgui-log tcp".into())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    // Each client gets its own writer so a slow one can't hold up the rest
+                    let (sender, receiver) = mpsc::sync_channel(CLIENT_BACKLOG);
+                    thread::spawn(move || serve(stream, receiver));
+                    accepted.lock().unwrap().push(sender);
+                }
+            })?;
+
+        Ok(TcpSink { clients })
+    }
+
+    pub fn write(&self, text: &str) {
+        let text: Arc<str> = text.into();
+        // Writers exit when their client disconnects, which is when we forget them
+        self.clients.lock().unwrap().retain(|client| {
+            !matches!(
+                client.try_send(text.clone()),
+                Err(mpsc::TrySendError::Disconnected(_))
+            )
+        });
+    }
+}
+
+fn serve(mut stream: TcpStream, lines: mpsc::Receiver<Arc<str>>) {
+    for line in lines {
+        if stream.write_all(line.as_bytes()).is_err() {
+            break;
+        }
+    }
+}
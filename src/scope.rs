@@ -0,0 +1,49 @@
+use crate::{filter_from_u8, LevelFilter};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The level read by the installed logger, if any
+static LEVEL: Mutex<Option<Arc<AtomicU8>>> = Mutex::new(None);
+
+pub(crate) fn register(level: Arc<AtomicU8>) {
+    *LEVEL.lock().unwrap() = Some(level);
+}
+
+/// Restores the previous level when dropped, see `verbosity_scope`
+#[must_use = "the level is restored as soon as the guard is dropped"]
+pub struct VerbosityScope {
+    level: Option<(Arc<AtomicU8>, u8)>,
+    max_level: LevelFilter,
+}
+
+/// Log at least as verbosely as `level` until the returned guard is dropped.
+///
+/// ```no_run
+/// # fn reproduce_bug() {}
+/// let _verbose = imgui_log::verbosity_scope(imgui_log::LevelFilter::Trace);
+/// reproduce_bug();
+/// // Back to the previous level here, even if reproduce_bug panics
+/// ```
+pub fn verbosity_scope(level: LevelFilter) -> VerbosityScope {
+    let max_level = log::max_level();
+    log::set_max_level(max_level.max(level));
+
+    let shared = LEVEL.lock().unwrap().clone();
+    let level = shared.map(|shared| {
+        let previous = shared.load(Ordering::Relaxed);
+        let raised = filter_from_u8(previous).max(level);
+        shared.store(raised as u8, Ordering::Relaxed);
+        (shared, previous)
+    });
+
+    VerbosityScope { level, max_level }
+}
+
+impl Drop for VerbosityScope {
+    fn drop(&mut self) {
+        if let Some((shared, previous)) = &self.level {
+            shared.store(*previous, Ordering::Relaxed);
+        }
+        log::set_max_level(self.max_level);
+    }
+}
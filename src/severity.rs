@@ -0,0 +1,55 @@
+use log::{Level, LevelFilter};
+use std::cmp::Ordering;
+
+/// A log level with comparisons that read the way you would say them
+///
+/// `log::Level` orders `Error` as the *smallest* level,
+/// which makes `level <= Level::Warn` mean "warn or worse".
+/// `Severity(level).is_at_least(Level::Warn)` says the same thing plainly.
+///
+/// Severities are also ordered by how serious they are, `Error > Warn > Info > Debug > Trace`,
+/// the opposite of `log::Level`'s `Ord`. Use this when sorting for display so that
+/// "most severe first" puts errors on top.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Severity(pub Level);
+
+impl Severity {
+    /// True if this is as severe as `level`, or more
+    pub fn is_at_least(self, level: Level) -> bool {
+        self >= Severity(level)
+    }
+
+    /// True if `filter` lets this level through
+    pub fn passes(self, filter: LevelFilter) -> bool {
+        self.0 <= filter
+    }
+
+    /// Name of the level padded to 5 characters, e.g. `"INFO "`
+    pub fn name_padded(self) -> &'static str {
+        match self.0 {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN ",
+            Level::Info => "INFO ",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+impl From<Level> for Severity {
+    fn from(level: Level) -> Self {
+        Severity(level)
+    }
+}
+
+impl Ord for Severity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl PartialOrd for Severity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
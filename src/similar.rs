@@ -0,0 +1,137 @@
+//! Grouping of lines that only differ by an argument, e.g.
+//! `loaded asset foo` and `loaded asset bar`.
+//!
+//! Messages are split on whitespace and any word containing a digit is treated as a
+//! placeholder. Consecutive lines with the same level and target are grouped when
+//! the rest of their words match, except for at most one word in the same position.
+//!
+//! The same word by word comparison finds what changed from one line to the next
+//! for `LogWindow::set_highlight_diffs`.
+
+use crate::LogLine;
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Split `lines` into runs of similar lines, never joining a line onto the one before `marker`
+pub(crate) fn group(lines: &[(usize, &LogLine)], marker: Option<usize>) -> Vec<Range<usize>> {
+    let mut groups = vec![];
+    let mut start = 0;
+    // The one word that is allowed to differ within the current group
+    let mut varying = None;
+
+    for n in 1..=lines.len() {
+        let joined = lines.get(n).and_then(|&(i, line)| {
+            let first = lines[start].1;
+            if Some(i) == marker || line.level != first.level || line.target != first.target {
+                return None;
+            }
+            match difference(&first.text, &line.text)? {
+                None => Some(varying),
+                Some(word) if varying.is_none() || varying == Some(word) => Some(Some(word)),
+                Some(_) => None,
+            }
+        });
+
+        match joined {
+            Some(word) => varying = word,
+            None => {
+                groups.push(start..n);
+                start = n;
+                varying = None;
+            }
+        }
+    }
+    groups
+}
+
+/// The first line of a group with every word that varies replaced by `<N variants>`
+pub(crate) fn template(lines: &[(usize, &LogLine)]) -> String {
+    let split: Vec<Vec<&str>> = lines
+        .iter()
+        .map(|(_, l)| l.text.split_whitespace().collect())
+        .collect();
+
+    (0..split[0].len())
+        .map(|word| {
+            let variants: HashSet<&str> = split.iter().map(|words| words[word]).collect();
+            if variants.len() > 1 {
+                format!("<{} variants>", variants.len())
+            } else {
+                split[0][word].to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// `None` if `a` and `b` are not similar, otherwise the position of the word that differs
+fn difference(a: &str, b: &str) -> Option<Option<usize>> {
+    let mut a = a.split_whitespace().map(normalize);
+    let mut b = b.split_whitespace().map(normalize);
+    let mut differs = None;
+
+    for word in 0.. {
+        match (a.next(), b.next()) {
+            (None, None) => break,
+            (Some(a), Some(b)) if a == b => {}
+            (Some(_), Some(_)) if differs.is_none() => differs = Some(word),
+            _ => return None,
+        }
+    }
+    Some(differs)
+}
+
+fn normalize(word: &str) -> &str {
+    if word.bytes().any(|b| b.is_ascii_digit()) {
+        "#"
+    } else {
+        word
+    }
+}
+
+/// `text` split into words (each with the whitespace after it) and whether the word differs
+/// from the same word of `previous`, or `None` if the lines are too different to compare.
+///
+/// Lines are compared when they have as many words and at most half of them changed.
+pub(crate) fn diff<'a>(previous: &str, text: &'a str) -> Option<Vec<(bool, &'a str)>> {
+    let previous: Vec<&str> = previous.split_whitespace().collect();
+    let words = words(text);
+    if words.len() != previous.len() {
+        return None;
+    }
+
+    let diff: Vec<(bool, &str)> = words
+        .iter()
+        .zip(previous)
+        .map(|(word, previous)| (word.trim() != previous, *word))
+        .collect();
+    let changed = diff.iter().filter(|(changed, _)| *changed).count();
+    if changed == 0 || changed * 2 > diff.len() {
+        return None;
+    }
+    Some(diff)
+}
+
+/// Like `split_whitespace`, but keeping the whitespace around each word
+/// so that joining them gives back `text`
+fn words(text: &str) -> Vec<&str> {
+    let mut words = vec![];
+    let mut start = 0;
+    let mut in_word = false;
+    let mut in_space = false;
+    for (i, c) in text.char_indices() {
+        if c.is_whitespace() {
+            in_space = in_word;
+        } else if in_space {
+            words.push(&text[start..i]);
+            start = i;
+            in_space = false;
+        } else {
+            in_word = true;
+        }
+    }
+    if in_word {
+        words.push(&text[start..]);
+    }
+    words
+}
@@ -0,0 +1,42 @@
+use log::Level;
+use std::time::Instant;
+
+/// Logs how long it was alive for when dropped, see `timed`
+#[must_use = "the time is logged as soon as the guard is dropped"]
+pub struct Timed {
+    label: String,
+    level: Level,
+    start: Instant,
+}
+
+/// Time a block of code, logging `"<label> took <N>ms"` when the returned guard is dropped.
+///
+/// ```no_run
+/// # fn load_level() {}
+/// {
+///     let _timer = imgui_log::timed("load level");
+///     load_level();
+/// } // logs "load level took 12.3ms" at Debug
+/// ```
+pub fn timed(label: impl Into<String>) -> Timed {
+    Timed {
+        label: label.into(),
+        level: Level::Debug,
+        start: Instant::now(),
+    }
+}
+
+impl Timed {
+    /// Log the time at `level` instead of Debug
+    pub fn level(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+}
+
+impl Drop for Timed {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed().as_secs_f64() * 1000.;
+        log::log!(self.level, "{} took {:.1}ms", self.label, elapsed);
+    }
+}
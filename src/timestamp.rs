@@ -0,0 +1,64 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How timestamps are rendered in front of each log line
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TimestampFormat {
+    /// No timestamp
+    #[default]
+    None,
+    /// Seconds since the logger was started, e.g. `12.3s`
+    RelativeSecs,
+    /// Local wall clock time using a `chrono` format string, e.g. `"%H:%M:%S%.3f"`
+    #[cfg(feature = "chrono")]
+    WallClock(String),
+    /// Milliseconds since the unix epoch
+    UnixMillis,
+}
+
+impl TimestampFormat {
+    /// Render `time` according to this format.
+    ///
+    /// `start` is the time the logger was created, used for relative formats.
+    /// Returns `None` when timestamps are disabled.
+    pub fn format(&self, time: SystemTime, start: SystemTime) -> Option<String> {
+        match self {
+            TimestampFormat::None => None,
+            TimestampFormat::RelativeSecs => {
+                let elapsed = time.duration_since(start).unwrap_or_default();
+                Some(secs(elapsed.as_secs_f32()))
+            }
+            #[cfg(feature = "chrono")]
+            TimestampFormat::WallClock(fmt) => {
+                let local: chrono::DateTime<chrono::Local> = time.into();
+                Some(local.format(fmt).to_string())
+            }
+            TimestampFormat::UnixMillis => {
+                let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+                Some(since_epoch.as_millis().to_string())
+            }
+        }
+    }
+
+    /// Everything about when a line was logged that goes in front of it: the timestamp
+    /// in this format, then the frame and its elapsed time if given, e.g. `[00042][1.2s] `
+    pub(crate) fn prefix(
+        &self,
+        time: SystemTime,
+        start: SystemTime,
+        frame: Option<(u64, f32)>,
+    ) -> String {
+        let mut prefix = String::new();
+        if let Some(stamp) = self.format(time, start) {
+            prefix += &format!("[{}] ", stamp);
+        }
+        if let Some((frame, elapsed)) = frame {
+            prefix += &format!("[{:05}][{}] ", frame, secs(elapsed));
+        }
+        prefix
+    }
+}
+
+/// Seconds as shown in timestamps, e.g. `12.3s`
+fn secs(secs: f32) -> String {
+    format!("{:.1}s", secs)
+}
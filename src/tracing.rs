@@ -0,0 +1,93 @@
+use crate::{ChanneledLogger, Level, LoggerHandle};
+use log::Record;
+use std::fmt;
+use tracing_core::field::{Field, Visit};
+use tracing_core::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// A `tracing_subscriber` layer that logs events like `log` records,
+/// to the window and every other sink of the logger, see `init_tracing`.
+///
+/// An event's `message` becomes the text of the line, its other fields
+/// are shown under it like key/values logged with the `kv` feature.
+///
+/// ```no_run
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let (window, layer) = imgui_log::init_tracing(imgui_log::LoggerConfig::default());
+/// let subscriber = tracing_subscriber::Registry::default().with(layer);
+/// tracing::subscriber::set_global_default(subscriber).unwrap();
+/// ```
+pub struct LogLayer {
+    logger: ChanneledLogger,
+}
+
+impl LogLayer {
+    /// Log events with `logger`, e.g. one built with `ChanneledLogger::new`
+    pub fn new(logger: ChanneledLogger) -> Self {
+        LogLayer { logger }
+    }
+
+    /// A handle to this layer's window, e.g. to `toast` or `set_enabled` it
+    pub fn handle(&self) -> LoggerHandle {
+        self.logger.handle()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        let message = fields.message.take().unwrap_or_default();
+
+        self.logger.log_with_fields(
+            &Record::builder()
+                .level(level(metadata.level()))
+                .target(metadata.target())
+                .module_path(metadata.module_path())
+                .file(metadata.file())
+                .line(metadata.line())
+                .args(format_args!("{}", message))
+                .build(),
+            || fields.fields,
+        );
+    }
+}
+
+fn level(level: &tracing_core::Level) -> Level {
+    match *level {
+        tracing_core::Level::ERROR => Level::Error,
+        tracing_core::Level::WARN => Level::Warn,
+        tracing_core::Level::INFO => Level::Info,
+        tracing_core::Level::DEBUG => Level::Debug,
+        _ => Level::Trace,
+    }
+}
+
+/// The `message` of an event and its other fields, as text
+#[derive(Default)]
+struct Fields {
+    message: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl Fields {
+    fn push(&mut self, field: &Field, value: String) {
+        if field.name() == "message" {
+            self.message = Some(value);
+        } else {
+            self.fields.push((field.name().to_owned(), value));
+        }
+    }
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, format!("{:?}", value));
+    }
+}
@@ -0,0 +1,2157 @@
+use crate::diagnostics::{self, Diagnostics};
+use crate::intern::Interner;
+use crate::{
+    filter_from_u8, markup, similar, Clock, LogColors, LogLine, Severity, Spill, SystemClock,
+};
+use imgui::im_str;
+use log::{Level, LevelFilter};
+use std::borrow::Cow;
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many frames the title bar flashes for after an error
+const FLASH_FRAMES: u32 = 60;
+
+/// How many seconds the rate sparkline covers
+const RATE_SECONDS: usize = 60;
+
+/// How many submitted filters are remembered by default, see `set_filter_history_len`
+const FILTER_HISTORY: usize = 20;
+
+/// Lines around the clicked one copied by "Copy with context", see `set_copy_context`
+const COPY_CONTEXT: usize = 3;
+
+/// Most lines allocated up front by `set_max_lines`, larger caps grow as lines arrive
+const PREALLOCATED_LINES: usize = 1 << 16;
+
+/// Width in pixels of the severity stripe
+const STRIPE_WIDTH: f32 = 3.;
+
+/// Space in pixels on either side of a badge's label
+const BADGE_PADDING: f32 = 4.;
+
+/// Opacity of the background behind words that changed, see `LogWindow::set_highlight_diffs`
+const DIFF_ALPHA: f32 = 0.35;
+
+/// Target of the lines added by `LogWindow::load_file`
+const HISTORY_TARGET: &str = "imgui_log::history";
+
+/// Called with lines as they are received, see `LogWindow::set_on_line`.
+/// Unlike the logger's `on_log` it runs on the UI thread, so it may be `FnMut`.
+type LineCallback = Box<dyn FnMut(&LogLine) + Send>;
+
+/// A snapshot of how much the window is holding on to, see `LogWindow::stats`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogStats {
+    /// Lines currently in the buffer
+    pub buffered: usize,
+    /// Lines the logger threw away because the window fell behind
+    pub dropped: usize,
+    /// Lines removed from the buffer to stay under `set_max_lines`
+    pub evicted: usize,
+    /// Bytes of text in the buffer
+    pub bytes: usize,
+}
+
+/// What was clicked while drawing a line, see `LogWindow::draw_line`
+struct LineClicks {
+    /// Right clicked, to open the context menu
+    menu: bool,
+    /// The arrow in front of a line with fields
+    expand: bool,
+}
+
+/// A button (or group of buttons) above the log, see `LogWindow::set_toolbar`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ToolbarItem {
+    /// Opens the popup with the auto-scroll and marker settings
+    Options,
+    Clear,
+    /// Copy the visible lines to the clipboard
+    Copy,
+    /// Pause/Resume following new lines
+    Pause,
+    /// Errors / Warnings+ / All presets for the shown level
+    Levels,
+    /// Toggle drawing every line, only shown when a render limit hides some
+    ShowAll,
+    /// The filter text box
+    Filter,
+    /// Prev/Next bookmark, only shown when a line is bookmarked
+    Bookmarks,
+    /// A sparkline of lines per second
+    Rate,
+    /// The time range slider, only shown when enabled in the Options popup
+    TimeRange,
+    /// Write the visible lines to the file set with `LogWindow::set_save_path`
+    Save,
+    /// Scroll to the newest line
+    Bottom,
+    /// A divider between groups of buttons
+    Separator,
+}
+
+/// The imgui frontend for ChanneledLogger.
+/// Call `build` during your rendering stage
+///
+/// Right click a line to bookmark or copy it.
+pub struct LogWindow {
+    /// See `LoggerConfig::name`
+    name: Option<String>,
+    self_log: bool,
+    buf: VecDeque<LogLine>,
+    max_lines: Option<usize>,
+    channel: mpsc::Receiver<LogLine>,
+    autoscroll: bool,
+    colors: LogColors,
+    /// The colors in use before switching to the color blind palette in the Options popup
+    replaced_colors: Option<LogColors>,
+    max_sync: Option<usize>,
+    child_size: [f32; 2],
+    tabs: Vec<(String, String)>,
+    /// `(target prefix, label, color)`, see `set_badges`
+    badges: Vec<(String, String, [f32; 4])>,
+    case_sensitive_targets: bool,
+    active_tab: usize,
+    filter: imgui::ImString,
+    /// Submitted filters, newest first
+    filter_history: VecDeque<String>,
+    filter_history_len: usize,
+    /// Which `filter_history` entry is in the filter box, `None` while typing a new one
+    history_pos: Option<usize>,
+    /// What was typed before going up the history, restored when coming back down
+    filter_draft: String,
+    /// Whether the filter box had keyboard focus last frame
+    filter_active: bool,
+    fuzzy: bool,
+    /// Least severe level that is shown, see `set_shown_level`
+    shown_level: LevelFilter,
+    /// Least severe level that is kept at all, see `set_capture_floor`
+    capture_floor: Arc<AtomicU8>,
+    /// Only show lines logged within `[from, to]` seconds, see `line_secs`
+    time_range: Option<[f32; 2]>,
+    /// Should be the logger's clock, line timestamps are compared to it
+    clock: Arc<dyn Clock>,
+    created: SystemTime,
+    /// Index of the first line shown at the top of the scroll region last frame
+    top_line: Option<usize>,
+    /// Line to scroll back to the top once the visible set has changed
+    scroll_anchor: Option<usize>,
+    at_bottom: bool,
+    /// Lines received while scrolled away from the bottom
+    unread: usize,
+    scroll_to_bottom: bool,
+    /// A line was wider than the log last frame, so the horizontal scrollbar is needed
+    overflows: bool,
+    /// Empty space below the last line, see `set_bottom_padding`
+    bottom_padding: f32,
+    errors: usize,
+    acknowledged_errors: usize,
+    interner: Option<Interner>,
+    on_line: Option<LineCallback>,
+    on_new_error: Option<LineCallback>,
+    flash_on_error: bool,
+    /// Frames left to flash the title bar for
+    flash: u32,
+    level_tags: bool,
+    messages_only: bool,
+    severity_stripe: bool,
+    /// Width in characters of the target column
+    target_column: Option<usize>,
+    /// Indexed by `level as usize - 1`
+    font_scale: [f32; 5],
+    paused: bool,
+    pause_on_error: bool,
+    parse_markup: bool,
+    fit_width: bool,
+    window_size: [f32; 2],
+    /// Index of the first line logged after the marker was set
+    marker: Option<usize>,
+    since_marker: bool,
+    render_limit: Option<usize>,
+    render_all: bool,
+    group_similar: bool,
+    summary_view: bool,
+    /// See `set_fixed_row_height`
+    fixed_row_height: Option<f32>,
+    highlight_diffs: bool,
+    /// Id of the line the context menu was opened on
+    context_line: Option<u64>,
+    /// Ids of the lines whose fields are shown
+    expanded: HashSet<u64>,
+    next_id: u64,
+    copy_context: usize,
+    interactive: bool,
+    show_toolbar: bool,
+    toolbar: Vec<ToolbarItem>,
+    save_path: PathBuf,
+    dropped: Option<Arc<AtomicUsize>>,
+    /// Lines the logger couldn't fit in the channel, taken after it
+    spill: Option<Spill>,
+    /// The logger's level, shown in the footer
+    level: Option<Arc<AtomicU8>>,
+    show_footer: bool,
+    embedded: bool,
+    /// Value of `dropped` when we last showed how many lines were dropped
+    dropped_seen: usize,
+    evicted: usize,
+    bytes: usize,
+    /// Lines received per second, oldest first, the last one being `rate_second`
+    rate: VecDeque<f32>,
+    /// Seconds since the unix epoch of the newest bucket in `rate`
+    rate_second: u64,
+}
+
+impl LogWindow {
+    pub fn new(channel: mpsc::Receiver<LogLine>) -> Self {
+        LogWindow {
+            name: None,
+            self_log: false,
+            buf: VecDeque::new(),
+            max_lines: None,
+            channel,
+            autoscroll: false,
+            colors: LogColors::default(),
+            replaced_colors: None,
+            max_sync: None,
+            child_size: [0., 0.],
+            tabs: vec![],
+            badges: vec![],
+            case_sensitive_targets: false,
+            active_tab: 0,
+            filter: imgui::ImString::with_capacity(256),
+            filter_history: VecDeque::new(),
+            filter_history_len: FILTER_HISTORY,
+            history_pos: None,
+            filter_draft: String::new(),
+            filter_active: false,
+            fuzzy: false,
+            shown_level: LevelFilter::Trace,
+            capture_floor: Arc::new(AtomicU8::new(LevelFilter::Trace as u8)),
+            time_range: None,
+            clock: Arc::new(SystemClock),
+            created: SystemTime::now(),
+            top_line: None,
+            scroll_anchor: None,
+            at_bottom: true,
+            unread: 0,
+            scroll_to_bottom: false,
+            overflows: false,
+            bottom_padding: 0.,
+            errors: 0,
+            acknowledged_errors: 0,
+            interner: None,
+            on_line: None,
+            on_new_error: None,
+            flash_on_error: false,
+            flash: 0,
+            level_tags: false,
+            messages_only: false,
+            severity_stripe: false,
+            target_column: None,
+            font_scale: [1.; 5],
+            paused: false,
+            pause_on_error: false,
+            parse_markup: false,
+            fit_width: false,
+            window_size: [0., 0.],
+            marker: None,
+            since_marker: false,
+            render_limit: None,
+            render_all: false,
+            group_similar: false,
+            summary_view: false,
+            fixed_row_height: None,
+            highlight_diffs: false,
+            context_line: None,
+            expanded: HashSet::new(),
+            next_id: 1,
+            copy_context: COPY_CONTEXT,
+            interactive: true,
+            show_toolbar: true,
+            toolbar: vec![
+                ToolbarItem::Options,
+                ToolbarItem::Clear,
+                ToolbarItem::Copy,
+                ToolbarItem::Pause,
+                ToolbarItem::Levels,
+                ToolbarItem::ShowAll,
+                ToolbarItem::Filter,
+                ToolbarItem::Bookmarks,
+                ToolbarItem::Rate,
+                ToolbarItem::TimeRange,
+            ],
+            save_path: PathBuf::from("log.txt"),
+            dropped: None,
+            spill: None,
+            level: None,
+            show_footer: false,
+            embedded: false,
+            dropped_seen: 0,
+            evicted: 0,
+            bytes: 0,
+            rate: VecDeque::from(vec![0.; RATE_SECONDS]),
+            rate_second: 0,
+        }
+    }
+}
+
+impl LogWindow {
+    /// Receive the lines logged since the last frame. Needs no `Ui`, so that `build`
+    /// can call it whether or not the window's contents are drawn.
+    fn sync(&mut self) {
+        let limit = self.max_sync.unwrap_or(usize::MAX);
+        let mut received = 0;
+        while received < limit {
+            match self.channel.try_recv() {
+                Ok(line) => self.capture(line),
+                Err(_) => break,
+            }
+            received += 1;
+        }
+        // Only once the channel is empty, the spilled lines came after what was in it
+        if let Some(spill) = self.spill.clone().filter(|_| received < limit) {
+            for line in spill.take(limit - received) {
+                self.capture(line);
+            }
+        }
+
+        // Lines are only dropped while the channel is full, so right after what we just
+        // received is where they would have been
+        let dropped = self.stats().dropped;
+        if dropped > self.dropped_seen {
+            let text = format!("--- {} messages dropped ---", dropped - self.dropped_seen);
+            self.dropped_seen = dropped;
+            self.push(diagnostics::line(Level::Warn, &text));
+        }
+
+        // Lines dropped by max_lines leave their text behind in the pool
+        let len = self.buf.len();
+        if let Some(interner) = &mut self.interner {
+            if interner.len() > 2 * len + 64 {
+                interner.prune();
+            }
+        }
+    }
+
+    /// Add a received line to the buffer, unless it is below the capture floor
+    fn capture(&mut self, line: LogLine) {
+        let floor = filter_from_u8(self.capture_floor.load(Ordering::Relaxed));
+        if Severity(line.level).passes(floor) {
+            self.push(line);
+        }
+    }
+
+    /// Add a received line to the buffer
+    fn push(&mut self, mut line: LogLine) {
+        line.id = self.next_id();
+        if self.paused || !self.at_bottom {
+            self.unread += 1;
+        }
+        if self.max_lines.is_some_and(|max| self.buf.len() >= max) {
+            self.pop_oldest();
+        }
+        if line.level == Level::Error {
+            self.errors += 1;
+            if self.flash_on_error {
+                self.flash = FLASH_FRAMES;
+            }
+            if let Some(on_new_error) = &mut self.on_new_error {
+                on_new_error(&line);
+            }
+            if self.pause_on_error && !self.paused {
+                self.paused = true;
+                self.scroll_anchor = Some(self.buf.len());
+            }
+        }
+        if let Some(interner) = &mut self.interner {
+            line.text = interner.intern(line.text);
+            line.target = interner.intern(line.target);
+        }
+        if let Some(on_line) = &mut self.on_line {
+            on_line(&line);
+        }
+        self.bytes += line.text.len();
+        self.count_rate(line.timestamp);
+        self.buf.push_back(line);
+    }
+
+    /// Add a line logged at `timestamp` to the lines per second
+    fn count_rate(&mut self, timestamp: SystemTime) {
+        let second = unix_seconds(timestamp);
+        self.advance_rate(second);
+        let age = (self.rate_second - second.min(self.rate_second)) as usize;
+        if age < RATE_SECONDS {
+            self.rate[RATE_SECONDS - 1 - age] += 1.;
+        }
+    }
+
+    /// Start new buckets up to `second`, dropping the oldest ones
+    fn advance_rate(&mut self, second: u64) {
+        let new = second
+            .saturating_sub(self.rate_second)
+            .min(RATE_SECONDS as u64);
+        for _ in 0..new {
+            self.rate.pop_front();
+            self.rate.push_back(0.);
+        }
+        self.rate_second = self.rate_second.max(second);
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Drop the oldest line, keeping the indices we track pointing at the same lines
+    fn pop_oldest(&mut self) {
+        if let Some(line) = self.buf.pop_front() {
+            self.bytes -= line.text.len();
+            self.evicted += 1;
+            self.expanded.remove(&line.id);
+        }
+        self.top_line = self.top_line.and_then(|i| i.checked_sub(1));
+        self.scroll_anchor = self.scroll_anchor.and_then(|i| i.checked_sub(1));
+        self.marker = self.marker.map(|i| i.saturating_sub(1));
+    }
+
+    /// True if an error has been logged since the last call to `acknowledge`.
+    ///
+    /// Errors are acknowledged automatically while the window is focused.
+    pub fn has_unseen_errors(&self) -> bool {
+        self.errors > self.acknowledged_errors
+    }
+
+    /// The most recent error still in the buffer
+    pub fn last_error(&self) -> Option<&LogLine> {
+        self.buf.iter().rev().find(|l| l.level == Level::Error)
+    }
+
+    /// Show the most recent error in a tooltip while the previous item is hovered.
+    ///
+    /// ```no_run
+    /// # fn hud(ui: &imgui::Ui, log: &imgui_log::LogWindow) {
+    /// ui.text("(!)");
+    /// log.error_tooltip(ui);
+    /// # }
+    /// ```
+    pub fn error_tooltip(&self, ui: &imgui::Ui) {
+        if let Some(line) = self.last_error().filter(|_| ui.is_item_hovered()) {
+            ui.tooltip(|| ui.text_colored(self.colors.error, &line.text));
+        }
+    }
+
+    /// Mark all errors logged so far as seen
+    pub fn acknowledge(&mut self) {
+        self.acknowledged_errors = self.errors;
+    }
+
+    pub fn clear(&mut self) {
+        self.buf.clear();
+        self.bytes = 0;
+        self.top_line = None;
+        self.scroll_anchor = None;
+        self.context_line = None;
+        self.expanded.clear();
+        self.unread = 0;
+        self.marker = self.marker.map(|_| 0);
+        if let Some(interner) = &mut self.interner {
+            interner.prune();
+        }
+    }
+
+    /// Undo what was changed from the toolbar and Options popup: the colors, filter,
+    /// shown level, time range, tab, marker filter, auto-scroll, pause, copy context
+    /// and capture floor.
+    ///
+    /// The lines are kept, unlike `clear`. Lines already thrown away by the capture floor
+    /// don't come back.
+    pub fn reset_view(&mut self) {
+        self.colors = LogColors::default();
+        self.replaced_colors = None;
+        self.filter.clear();
+        self.fuzzy = false;
+        self.shown_level = LevelFilter::Trace;
+        self.time_range = None;
+        self.active_tab = 0;
+        self.since_marker = false;
+        self.render_all = false;
+        self.autoscroll = false;
+        self.paused = false;
+        self.scroll_anchor = None;
+        self.scroll_to_bottom = true;
+        self.copy_context = COPY_CONTEXT;
+        self.capture_floor
+            .store(LevelFilter::Trace as u8, Ordering::Relaxed);
+    }
+
+    /// Remove every line and return them, oldest first, e.g. to hand them to an exporter.
+    ///
+    /// The window is left empty, as after `clear`.
+    pub fn take(&mut self) -> Vec<LogLine> {
+        let lines = self.buf.drain(..).collect();
+        self.clear();
+        lines
+    }
+
+    /// Name this window, e.g. to tell several apart. Also set by `LoggerConfig::name`.
+    pub fn set_name<S: Into<String>>(&mut self, name: S) {
+        self.name = Some(name.into());
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Write `message` about our own problems to stderr, and to the log with `self_log`
+    fn report(&mut self, message: &str) {
+        // Pushed below rather than sent, this is the window the channel goes to
+        Diagnostics::new(self.name.as_deref(), None).report(message);
+        if self.self_log {
+            self.push(diagnostics::line(Level::Warn, message));
+        }
+    }
+
+    /// See `LoggerConfig::self_log`
+    pub(crate) fn set_self_log(&mut self, self_log: bool) {
+        self.self_log = self_log;
+    }
+
+    /// Write the visible lines to `path`, the same text Copy puts on the clipboard
+    pub fn save_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.visible_text())
+    }
+
+    /// Where the Save button writes to, `log.txt` in the working directory by default
+    pub fn set_save_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.save_path = path.into();
+    }
+
+    /// Load a log file from a previous session in front of the current lines,
+    /// e.g. to see what led up to a crash.
+    ///
+    /// Each line of the file becomes a `LogLine` with the target `imgui_log::history`.
+    /// The level is guessed from a `LEVEL:` word as written by the default formatter,
+    /// anything else is `Info`. If `set_max_lines` is in use only the end of the file
+    /// that fits is loaded.
+    pub fn load_file<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let modified = fs::metadata(path)?
+            .modified()
+            .unwrap_or_else(|_| SystemTime::now());
+        let bytes = fs::read(path)?;
+        let text = String::from_utf8_lossy(&bytes);
+
+        let lines: Vec<&str> = text.lines().collect();
+        let room = self
+            .max_lines
+            .map_or(usize::MAX, |max| max.saturating_sub(self.buf.len()));
+        let skip = lines.len().saturating_sub(room);
+        let count = lines.len() - skip;
+
+        for text in lines[skip..].iter().rev() {
+            let mut line = LogLine::new(guess_level(text), HISTORY_TARGET, format!("{}\n", text));
+            line.timestamp = modified;
+            line.id = self.next_id();
+            self.bytes += line.text.len();
+            self.buf.push_front(line);
+        }
+
+        self.top_line = self.top_line.map(|i| i + count);
+        self.scroll_anchor = self.scroll_anchor.map(|i| i + count);
+        self.marker = self.marker.map(|i| i + count);
+        Ok(())
+    }
+
+    /// Append the lines of a binary log written by `LoggerConfig::binary_file`,
+    /// e.g. to replay a captured session.
+    #[cfg(feature = "binary")]
+    pub fn load_binary<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let lines = crate::binary::read(path.as_ref())?;
+        self.extend(lines);
+        Ok(())
+    }
+
+    /// Append many lines at once, e.g. to replay a saved session on startup.
+    ///
+    /// Unlike logging them, nothing goes through the channel so nothing is dropped,
+    /// and the lines are not passed to `set_on_line`/`set_on_new_error` or counted as
+    /// unseen errors. Only the newest lines are kept if `set_max_lines` is exceeded.
+    pub fn extend<I: IntoIterator<Item = LogLine>>(&mut self, lines: I) {
+        let lines = lines.into_iter();
+        // Never past the cap, the oldest lines make room for the rest
+        let room = self
+            .max_lines
+            .map_or(usize::MAX, |max| max.saturating_sub(self.buf.len()));
+        self.buf.reserve(lines.size_hint().0.min(room));
+        for mut line in lines {
+            line.id = self.next_id();
+            if let Some(interner) = &mut self.interner {
+                line.text = interner.intern(line.text);
+                line.target = interner.intern(line.target);
+            }
+            // Make room first, so that a capped buffer never grows past its allocation
+            if self.max_lines.is_some_and(|max| self.buf.len() >= max) {
+                self.pop_oldest();
+            }
+            self.bytes += line.text.len();
+            self.buf.push_back(line);
+        }
+    }
+
+    /// Every line received so far, oldest first
+    pub fn lines(&self) -> &VecDeque<LogLine> {
+        &self.buf
+    }
+
+    /// Every line matching `predicate`, oldest first
+    pub fn filter_lines<F: Fn(&LogLine) -> bool>(&self, predicate: F) -> Vec<&LogLine> {
+        self.buf.iter().filter(|l| predicate(l)).collect()
+    }
+
+    /// Number of lines matching `predicate`
+    pub fn count_matching<F: Fn(&LogLine) -> bool>(&self, predicate: F) -> usize {
+        self.buf.iter().filter(|l| predicate(l)).count()
+    }
+
+    pub fn stats(&self) -> LogStats {
+        LogStats {
+            buffered: self.buf.len(),
+            dropped: self
+                .dropped
+                .as_ref()
+                .map_or(0, |d| d.load(Ordering::Relaxed)),
+            evicted: self.evicted,
+            bytes: self.bytes,
+        }
+    }
+
+    /// Where the logger counts the lines it dropped, for `stats`
+    pub(crate) fn set_dropped_counter(&mut self, dropped: Arc<AtomicUsize>) {
+        self.dropped = Some(dropped);
+    }
+
+    /// Where the logger puts the lines that didn't fit in the channel
+    pub(crate) fn set_spill(&mut self, spill: Spill) {
+        self.spill = Some(spill);
+    }
+
+    /// Where to read the logger's level from, for the footer
+    pub(crate) fn set_level_source(&mut self, level: Arc<AtomicU8>) {
+        self.level = Some(level);
+    }
+
+    pub fn set_colors(&mut self, colors: LogColors) {
+        self.colors = colors;
+        self.replaced_colors = None;
+    }
+
+    /// Only keep the newest `max` lines, older ones are dropped as new ones arrive.
+    ///
+    /// Room for up to 65536 lines is reserved up front, so that the buffer of a full
+    /// window with a cap that size or less never reallocates. Each line's text is still
+    /// its own allocation. `None` (the default) keeps everything.
+    pub fn set_max_lines(&mut self, max: Option<usize>) {
+        let max = max.map(|max| max.max(1));
+        self.max_lines = max;
+        if let Some(max) = max {
+            while self.buf.len() > max {
+                self.pop_oldest();
+            }
+            let room = max.min(PREALLOCATED_LINES);
+            self.buf.reserve_exact(room.saturating_sub(self.buf.len()));
+        }
+    }
+
+    /// Limit how many lines are pulled from the logger each frame.
+    ///
+    /// Spreads a large burst over several frames to avoid a hitch.
+    /// Lines left behind stay in the channel, which is bounded, so a sustained
+    /// flood will cause new lines to be dropped sooner than without a limit.
+    /// `None` (the default) drains everything every frame.
+    pub fn set_max_sync_per_frame(&mut self, max: Option<usize>) {
+        self.max_sync = max;
+    }
+
+    /// Always follow new lines, even after scrolling up.
+    ///
+    /// This is the "Auto-scroll" checkbox in the Options popup.
+    pub fn set_autoscroll(&mut self, autoscroll: bool) {
+        self.autoscroll = autoscroll;
+    }
+
+    pub fn autoscroll(&self) -> bool {
+        self.autoscroll
+    }
+
+    /// Stop following new lines. They are still collected while paused.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Automatically pause, keeping the error in view, when an error is logged
+    pub fn set_pause_on_error(&mut self, pause_on_error: bool) {
+        self.pause_on_error = pause_on_error;
+    }
+
+    /// Render `{color:red}inline colors{/}` in messages.
+    ///
+    /// Colors can be a name (`red`, `green`, `blue`, `yellow`, `cyan`, `magenta`,
+    /// `orange`, `white`, `gray`, `black`), a level (`error`, `warn`, ...) to reuse
+    /// the window's `LogColors`, or a hex `#rrggbb`. Tags do not nest, and anything
+    /// that is not a complete valid tag is shown as is.
+    ///
+    /// Off by default so that messages containing braces are left alone.
+    pub fn set_parse_markup(&mut self, parse_markup: bool) {
+        self.parse_markup = parse_markup;
+    }
+
+    /// Show a fixed width `[INFO ]` tag in front of every line.
+    ///
+    /// Makes the level clear without relying on color or on the formatter including it.
+    pub fn set_level_tags(&mut self, level_tags: bool) {
+        self.level_tags = level_tags;
+    }
+
+    /// Only draw the message of each line, without the level, location or anything else
+    /// the formatter adds, e.g. for a notification feed. Lines keep their level color.
+    ///
+    /// Copy, Save and the log files still get the full lines.
+    /// Lines that were not logged through the `log` facade are drawn in full.
+    pub fn set_messages_only(&mut self, messages_only: bool) {
+        self.messages_only = messages_only;
+    }
+
+    /// The part of `line` that is drawn, see `set_messages_only`
+    fn shown_text<'a>(&self, line: &'a LogLine) -> &'a str {
+        match &line.message {
+            Some(message) if self.messages_only => message,
+            _ => &line.text,
+        }
+    }
+
+    /// Show the level as a thin colored stripe in front of each line,
+    /// and draw the text itself in the default text color.
+    ///
+    /// Colors given to a line directly (e.g. with `toast!`) still apply to its text.
+    pub fn set_severity_stripe(&mut self, severity_stripe: bool) {
+        self.severity_stripe = severity_stripe;
+    }
+
+    /// Show each line's target in a column `width` characters wide.
+    ///
+    /// Longer targets are shortened in the middle, e.g. `mycrate::re..der::mesh`,
+    /// and shown in full when hovered. `None` (the default) hides the column.
+    pub fn set_target_column(&mut self, width: Option<usize>) {
+        self.target_column = width;
+    }
+
+    /// Draw lines of `level` at `scale` times the normal text size, e.g. to make errors stand out.
+    ///
+    /// Changing the scale costs a little for every line drawn at a different size,
+    /// so prefer using it for rare levels. Lines return to a scale of 1 afterwards.
+    pub fn set_font_scale_for(&mut self, level: Level, scale: f32) {
+        self.font_scale[level as usize - 1] = scale;
+    }
+
+    /// Call `f` with every new line as the window receives it.
+    ///
+    /// This runs on the UI thread while the window is being built,
+    /// see `LoggerConfig::on_log` to be notified as soon as a line is logged.
+    pub fn set_on_line<F: FnMut(&LogLine) + Send + 'static>(&mut self, f: F) {
+        self.on_line = Some(Box::new(f));
+    }
+
+    /// Call `f` with every new error as the window receives it,
+    /// e.g. to ring a bell during long unattended runs.
+    pub fn set_on_new_error<F: FnMut(&LogLine) + Send + 'static>(&mut self, f: F) {
+        self.on_new_error = Some(Box::new(f));
+    }
+
+    /// Flash the title bar in the error color for a moment when an error is logged
+    pub fn set_flash_on_error(&mut self, flash_on_error: bool) {
+        self.flash_on_error = flash_on_error;
+    }
+
+    /// Share the storage of identical lines and targets.
+    ///
+    /// Saves memory on long sessions with repetitive logs,
+    /// at the cost of a hash lookup for every new line.
+    pub fn set_intern_strings(&mut self, intern: bool) {
+        if intern != self.interner.is_some() {
+            self.interner = if intern {
+                Some(Interner::default())
+            } else {
+                None
+            };
+        }
+    }
+
+    /// Bytes not allocated thanks to `set_intern_strings`, since it was enabled
+    pub fn interned_bytes_saved(&self) -> usize {
+        self.interner.as_ref().map_or(0, |i| i.saved())
+    }
+
+    /// Resize the window to fit the longest visible line the next time it is built.
+    ///
+    /// The height is left as is.
+    pub fn fit_width(&mut self) {
+        self.fit_width = true;
+    }
+
+    /// Size of the scrolling region below the buttons.
+    ///
+    /// Defaults to `[0., 0.]` which fills the rest of the window.
+    pub fn set_child_size(&mut self, size: [f32; 2]) {
+        self.child_size = size;
+    }
+
+    /// Mark the current end of the log, e.g. before reproducing a bug.
+    ///
+    /// The marker is shown as a separator, and `set_show_since_marker`
+    /// hides everything logged before it.
+    pub fn set_marker(&mut self) {
+        self.marker = Some(self.buf.len());
+    }
+
+    pub fn clear_marker(&mut self) {
+        self.marker = None;
+        self.since_marker = false;
+    }
+
+    /// Only show lines logged after the marker
+    pub fn set_show_since_marker(&mut self, since_marker: bool) {
+        self.since_marker = since_marker;
+    }
+
+    /// Only draw lines among the newest `limit`, while still keeping the whole buffer.
+    ///
+    /// Keeps frames cheap with a long history, which Copy still exports in full.
+    /// A "Show all" button temporarily draws everything.
+    /// `None` (the default) draws every line.
+    pub fn set_render_limit(&mut self, limit: Option<usize>) {
+        self.render_limit = limit;
+        self.render_all = false;
+    }
+
+    /// Collapse runs of lines that only differ by one word or by numbers,
+    /// e.g. `loaded asset foo` and `loaded asset bar`, into `loaded asset <2 variants>`.
+    ///
+    /// Groups can be expanded to see every line. Off by default since the lines have
+    /// to be compared every frame.
+    pub fn set_group_similar(&mut self, group_similar: bool) {
+        self.group_similar = group_similar;
+    }
+
+    /// Instead of one list, show a collapsible header per level with how many lines
+    /// it has, e.g. `Errors: 3`. Expanding a header lists that level's lines.
+    ///
+    /// The headers count the lines that pass the filter, tab and time range.
+    pub fn set_summary_view(&mut self, summary_view: bool) {
+        self.summary_view = summary_view;
+    }
+
+    /// Draw every line as a single row of exactly `height` pixels, `None` to draw lines in full.
+    ///
+    /// Only the rows in view are drawn, which keeps huge logs cheap without a render limit.
+    /// Lines are cut to their first line and to the width of the window, with the full
+    /// text shown when hovering them. Similar lines are not grouped, fields are not
+    /// expandable and the marker is not drawn in this mode. A height that isn't positive
+    /// is the same as `None`.
+    pub fn set_fixed_row_height(&mut self, height: Option<f32>) {
+        // There is no row to draw without a height
+        self.fixed_row_height = height.filter(|height| *height > 0.);
+    }
+
+    /// How many lines before and after to include with "Copy with context"
+    /// in a line's right click menu. Defaults to 3.
+    pub fn set_copy_context(&mut self, lines: usize) {
+        self.copy_context = lines;
+    }
+
+    /// Only draw the log itself, without any buttons, tabs, popups or context menus.
+    ///
+    /// For read only panels, e.g. a diagnostics overlay in a release build
+    /// that players should not be able to clear.
+    pub fn set_interactive(&mut self, interactive: bool) {
+        self.interactive = interactive;
+    }
+
+    /// Show a line below the log with the number of lines, errors, dropped lines,
+    /// the current level and whether new lines are being followed.
+    pub fn set_show_footer(&mut self, show_footer: bool) {
+        self.show_footer = show_footer;
+    }
+
+    /// Draw the lines straight into the window passed to `build`,
+    /// instead of a scrolling region of their own.
+    ///
+    /// Use this to compose the log into a larger layout, the lines then scroll with
+    /// the parent window and auto-scroll applies to it.
+    pub fn set_embedded(&mut self, embedded: bool) {
+        self.embedded = embedded;
+    }
+
+    /// Leave `pixels` of empty space below the newest line,
+    /// so that it isn't flush against the bottom edge while following the log.
+    pub fn set_bottom_padding(&mut self, pixels: f32) {
+        self.bottom_padding = pixels.max(0.);
+    }
+
+    /// Highlight the words of a line that changed from the line above it,
+    /// e.g. to follow a value that is logged every frame.
+    ///
+    /// Only lines with the same target and as many words, at most half of them different,
+    /// are compared. Off by default since it is done for every line, every frame.
+    pub fn set_highlight_diffs(&mut self, highlight_diffs: bool) {
+        self.highlight_diffs = highlight_diffs;
+    }
+
+    /// Hide the row of buttons (and the separator below it),
+    /// e.g. to embed the log as a plain scrollback pane driven from your own UI.
+    pub fn set_show_toolbar(&mut self, show_toolbar: bool) {
+        self.show_toolbar = show_toolbar;
+    }
+
+    /// Choose which buttons appear above the log, in order.
+    ///
+    /// Defaults to every `ToolbarItem` up to `TimeRange`, in the order they are declared.
+    pub fn set_toolbar(&mut self, items: Vec<ToolbarItem>) {
+        self.toolbar = items;
+    }
+
+    /// Match the filter as a subsequence, ignoring case, rather than as an exact substring.
+    ///
+    /// `ldasst` finds `loaded asset`. Also toggled by the checkbox next to the filter.
+    pub fn set_fuzzy_filter(&mut self, fuzzy: bool) {
+        self.fuzzy = fuzzy;
+    }
+
+    /// How many filters submitted with Enter are kept for Up/Down in the filter box, 20 by default.
+    pub fn set_filter_history_len(&mut self, len: usize) {
+        self.filter_history_len = len;
+        self.filter_history.truncate(len);
+        self.history_pos = None;
+    }
+
+    /// The filters submitted with Enter, newest first, e.g. to save them between runs
+    pub fn filter_history(&self) -> impl Iterator<Item = &str> {
+        self.filter_history.iter().map(String::as_str)
+    }
+
+    /// Replace the filter history, newest first, e.g. with one saved by a previous run
+    pub fn set_filter_history(&mut self, history: Vec<String>) {
+        self.filter_history = history.into();
+        self.filter_history.truncate(self.filter_history_len);
+        self.history_pos = None;
+    }
+
+    /// Only show lines at least as severe as `level`, e.g. `LevelFilter::Warn` for warnings and errors.
+    ///
+    /// Unlike the logger's level this only hides lines, they are still buffered.
+    /// This is also set by the Errors / Warnings+ / All buttons.
+    pub fn set_shown_level(&mut self, level: LevelFilter) {
+        self.shown_level = level;
+    }
+
+    /// Throw away received lines less severe than `level`, e.g. `LevelFilter::Info`
+    /// to never keep trace and debug lines in memory. Also set from the Options popup.
+    ///
+    /// Unlike `set_shown_level`, which only hides lines, lines below the floor are lost
+    /// for good: they are not buffered, and lowering the floor later doesn't bring them back.
+    /// They are still logged to stdout and files.
+    pub fn set_capture_floor(&mut self, level: LevelFilter) {
+        self.capture_floor.store(level as u8, Ordering::Relaxed);
+    }
+
+    /// The capture floor, as a `LevelFilter as u8`, to change it from elsewhere at runtime
+    pub fn capture_floor(&self) -> Arc<AtomicU8> {
+        self.capture_floor.clone()
+    }
+
+    /// Only show lines logged between `from` and `to` seconds, `None` to show all.
+    ///
+    /// Times are the frame source's elapsed time when available (e.g. with amethyst),
+    /// otherwise the seconds since the window was created.
+    /// This is also set by the time range slider.
+    pub fn set_time_range(&mut self, range: Option<(f32, f32)>) {
+        self.time_range = range.map(|(from, to)| [from, to]);
+    }
+
+    /// Read the time from `clock`, the same one as the logger's `LoggerConfig::clock`.
+    ///
+    /// `init_with_config` sets this up. Times in the time range slider count from
+    /// when this is called.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.created = clock.now();
+        self.clock = clock;
+    }
+
+    /// When `line` was logged, for the time range
+    fn line_secs(&self, line: &LogLine) -> f32 {
+        line.elapsed.unwrap_or_else(|| {
+            line.timestamp
+                .duration_since(self.created)
+                .map_or(0., |d| d.as_secs_f32())
+        })
+    }
+
+    /// The times of the oldest and newest lines
+    fn time_bounds(&self) -> [f32; 2] {
+        let secs = |line: Option<&LogLine>| line.map_or(0., |l| self.line_secs(l));
+        [secs(self.buf.front()), secs(self.buf.back())]
+    }
+
+    /// Split the log into tabs of `(label, target prefix)`.
+    ///
+    /// Each tab only shows lines whose target starts with its prefix,
+    /// ignoring case unless `set_case_sensitive_targets` is on.
+    /// An "All" tab showing every line is always included first.
+    pub fn set_tabs(&mut self, tabs: Vec<(String, String)>) {
+        self.tabs = tabs;
+        self.active_tab = 0;
+    }
+
+    /// Draw a small colored pill with a label, e.g. `NET`, in front of lines
+    /// whose target starts with a prefix, given as `(target prefix, label, color)`.
+    ///
+    /// The first matching badge is used. Case is ignored as for `set_tabs`.
+    pub fn set_badges(&mut self, badges: Vec<(String, String, [f32; 4])>) {
+        self.badges = badges;
+    }
+
+    /// Match the target prefixes of tabs and badges exactly,
+    /// rather than ignoring (ASCII) case, which is the default.
+    pub fn set_case_sensitive_targets(&mut self, case_sensitive: bool) {
+        self.case_sensitive_targets = case_sensitive;
+    }
+
+    /// True if `target` starts with `prefix`, the one place targets are matched
+    /// so that every option follows `set_case_sensitive_targets`
+    fn target_matches(&self, target: &str, prefix: &str) -> bool {
+        if self.case_sensitive_targets {
+            return target.starts_with(prefix);
+        }
+        target
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    }
+
+    fn is_visible(&self, line: &LogLine) -> bool {
+        if !Severity(line.level).passes(self.shown_level) {
+            return false;
+        }
+
+        let filter = self.filter.to_str();
+        if !filter.is_empty() {
+            let matches = if self.fuzzy {
+                fuzzy_matches(filter, &line.text)
+            } else {
+                line.text.contains(filter)
+            };
+            if !matches {
+                return false;
+            }
+        }
+
+        if let Some([from, to]) = self.time_range {
+            let secs = self.line_secs(line);
+            if secs < from || secs > to {
+                return false;
+            }
+        }
+
+        match self
+            .active_tab
+            .checked_sub(1)
+            .and_then(|i| self.tabs.get(i))
+        {
+            Some((_, prefix)) => self.target_matches(&line.target, prefix),
+            None => true,
+        }
+    }
+
+    /// Step through the filter history on Up/Down like a shell, returns whether the filter changed
+    fn recall_filter(&mut self, ui: &imgui::Ui) -> bool {
+        let up = ui.is_key_pressed(ui.key_index(imgui::Key::UpArrow));
+        let down = ui.is_key_pressed(ui.key_index(imgui::Key::DownArrow));
+        let last = match self.filter_history.len() {
+            0 => return false,
+            len => len - 1,
+        };
+        let pos = match (up, down, self.history_pos) {
+            (true, _, None) => {
+                self.filter_draft = self.filter.to_str().to_owned();
+                Some(0)
+            }
+            (true, _, Some(i)) => Some((i + 1).min(last)),
+            (false, true, Some(0)) => None,
+            (false, true, Some(i)) => Some(i - 1),
+            _ => return false,
+        };
+        if pos == self.history_pos {
+            return false;
+        }
+        self.history_pos = pos;
+        let text = match pos {
+            Some(i) => &self.filter_history[i],
+            None => &self.filter_draft,
+        };
+        self.filter.clear();
+        self.filter.push_str(text);
+        true
+    }
+
+    /// Remember the current filter, moving it to the front if it was already there
+    fn push_filter(&mut self) {
+        self.history_pos = None;
+        let filter = self.filter.to_str();
+        if filter.is_empty() || self.filter_history_len == 0 {
+            return;
+        }
+        if let Some(i) = self.filter_history.iter().position(|f| f == filter) {
+            self.filter_history.remove(i);
+        }
+        self.filter_history.push_front(filter.to_owned());
+        self.filter_history.truncate(self.filter_history_len);
+    }
+
+    /// The lines that pass every filter, with their index in the buffer
+    fn visible(&self) -> impl DoubleEndedIterator<Item = (usize, &LogLine)> + '_ {
+        self.visible_from(0)
+    }
+
+    /// The visible lines that are drawn, see `set_render_limit`
+    fn rendered(&self) -> impl DoubleEndedIterator<Item = (usize, &LogLine)> + '_ {
+        let start = match self.render_limit {
+            Some(limit) if !self.render_all => self.buf.len().saturating_sub(limit),
+            _ => 0,
+        };
+        self.visible_from(start)
+    }
+
+    fn visible_from(
+        &self,
+        start: usize,
+    ) -> impl DoubleEndedIterator<Item = (usize, &LogLine)> + '_ {
+        let start = match self.marker {
+            Some(marker) if self.since_marker => start.max(marker),
+            _ => start,
+        };
+        self.buf
+            .iter()
+            .enumerate()
+            .skip(start)
+            .filter(move |(_, l)| self.is_visible(l))
+    }
+
+    /// The lines currently shown (after filtering), joined into a single string
+    fn visible_text(&self) -> String {
+        self.join_text(self.visible().map(|(_, l)| l))
+    }
+
+    fn join_text<'a>(&self, lines: impl Iterator<Item = &'a LogLine>) -> String {
+        lines
+            .map(|l| {
+                if self.parse_markup {
+                    markup::strip(&l.text, &self.colors)
+                } else {
+                    l.to_string()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Handle the Copy and Clear buttons, returning the text to copy.
+    ///
+    /// Pressing both in the same frame copies what was on screen, then clears it.
+    fn copy_and_clear(&mut self, copy: bool, clear: bool) -> Option<String> {
+        let text = if copy {
+            Some(self.visible_text())
+        } else {
+            None
+        };
+        if clear {
+            self.clear();
+        }
+        text
+    }
+
+    /// The closest bookmarked line after (or before) the line at the top of the view
+    fn find_bookmark(&self, forward: bool) -> Option<usize> {
+        let top = self.top_line.unwrap_or(0);
+        let mut bookmarks = self.visible().filter(|(_, l)| l.bookmarked).map(|(i, _)| i);
+
+        if forward {
+            bookmarks.find(|&i| i > top)
+        } else {
+            bookmarks.rev().find(|&i| i < top)
+        }
+    }
+
+    /// Draw a single line, and its fields if expanded.
+    ///
+    /// `previous` is the line drawn just above it, for `set_highlight_diffs`.
+    fn draw_line(
+        &self,
+        ui: &imgui::Ui,
+        record: &LogLine,
+        previous: Option<&LogLine>,
+    ) -> LineClicks {
+        // As a string, an i32 id would collide once more than 2^32 lines have been received
+        let id = ui.push_id(&record.id.to_string());
+        let scale = self.font_scale[record.level as usize - 1];
+        if scale != 1. {
+            ui.set_window_font_scale(scale);
+        }
+        let level_color = self.colors.level(record.level);
+        // `None` draws in the default text color
+        let color = match record.color {
+            Some(color) => Some(color),
+            None if self.severity_stripe => None,
+            None => Some(level_color),
+        };
+        let text = |color: Option<[f32; 4]>, text: &str| match color {
+            Some(color) => ui.text_colored(color, text),
+            None => ui.text(text),
+        };
+
+        if self.severity_stripe {
+            let pos = ui.cursor_screen_pos();
+            let height = ui.text_line_height();
+            {
+                let draw_list = ui.get_window_draw_list();
+                draw_list
+                    .add_rect(pos, [pos[0] + STRIPE_WIDTH, pos[1] + height], level_color)
+                    .filled(true)
+                    .build();
+            }
+            ui.dummy([STRIPE_WIDTH * 2., height]);
+            ui.same_line(0.);
+        }
+        let expanded = self.expanded.contains(&record.id);
+        let mut expand = false;
+        let single_row = self.fixed_row_height.is_some();
+        if !single_row && (!record.fields.is_empty() || attachment(record).is_some()) {
+            ui.text_disabled(if expanded { "v " } else { "> " });
+            expand = ui.is_item_clicked(imgui::MouseButton::Left);
+            ui.same_line(0.);
+        }
+        if record.bookmarked {
+            ui.text_disabled("* ");
+            ui.same_line(0.);
+        }
+        let badge = self
+            .badges
+            .iter()
+            .find(|(prefix, _, _)| self.target_matches(&record.target, prefix));
+        if let Some((_, label, badge_color)) = badge {
+            let pos = ui.cursor_screen_pos();
+            let size = ui.calc_text_size(&imgui::ImString::new(label.as_str()), false, -1.);
+            let width = size[0] + BADGE_PADDING * 2.;
+            {
+                let draw_list = ui.get_window_draw_list();
+                draw_list
+                    .add_rect(pos, [pos[0] + width, pos[1] + size[1]], *badge_color)
+                    .filled(true)
+                    .rounding(size[1] / 2.)
+                    .build();
+                draw_list.add_text(
+                    [pos[0] + BADGE_PADDING, pos[1]],
+                    contrasting(*badge_color),
+                    label,
+                );
+            }
+            ui.dummy([width + BADGE_PADDING, size[1]]);
+            ui.same_line(0.);
+        }
+        if self.level_tags {
+            let tag = Severity(record.level).name_padded();
+            text(color, &format!("[{}] ", tag));
+            ui.same_line(0.);
+        }
+        if let Some(width) = self.target_column {
+            let (target, shortened) = fit_column(&record.target, width);
+            ui.text_disabled(&format!("{} ", target));
+            if shortened && ui.is_item_hovered() {
+                ui.tooltip_text(&*record.target);
+            }
+            ui.same_line(0.);
+        }
+        let body = self.shown_text(record);
+        let diff = previous
+            .filter(|p| self.highlight_diffs && !self.parse_markup && p.target == record.target)
+            .and_then(|p| similar::diff(self.shown_text(p), body));
+        if let Some(diff) = diff {
+            let highlight = [level_color[0], level_color[1], level_color[2], DIFF_ALPHA];
+            for (n, (changed, word)) in diff.iter().enumerate() {
+                if n > 0 {
+                    ui.same_line(0.);
+                }
+                if *changed {
+                    let pos = ui.cursor_screen_pos();
+                    let size =
+                        ui.calc_text_size(&imgui::ImString::new(word.trim_end()), false, -1.);
+                    let draw_list = ui.get_window_draw_list();
+                    draw_list
+                        .add_rect(pos, [pos[0] + size[0], pos[1] + size[1]], highlight)
+                        .filled(true)
+                        .build();
+                }
+                text(color, word);
+            }
+        } else if single_row {
+            let (row, cut) = fit_width(ui, body, ui.content_region_avail()[0]);
+            text(color, &row);
+            if cut && ui.is_item_hovered() {
+                ui.tooltip_text(body);
+            }
+        } else if self.parse_markup {
+            let segments = markup::parse(body, &self.colors);
+            for (n, (segment_color, segment)) in segments.iter().enumerate() {
+                if n > 0 {
+                    ui.same_line(0.);
+                }
+                text(segment_color.or(color), segment);
+            }
+        } else {
+            text(color, body);
+        }
+        let menu = ui.is_item_clicked(imgui::MouseButton::Right);
+        if expanded && !single_row {
+            for (key, value) in &record.fields {
+                ui.text_disabled(&format!("    {}: {}", key, value));
+            }
+            #[cfg(feature = "attachments")]
+            {
+                if let Some(data) = attachment(record) {
+                    for row in crate::hexdump::rows(data) {
+                        ui.text_disabled(&format!("    {}", row));
+                    }
+                }
+            }
+        }
+        if scale != 1. {
+            ui.set_window_font_scale(1.);
+        }
+        id.pop(ui);
+        LineClicks { menu, expand }
+    }
+
+    /// The right click menu of the line at `context_line`
+    fn draw_line_menu(&mut self, ui: &imgui::Ui) {
+        let id = self.context_line;
+        let i = match self.buf.iter().position(|l| Some(l.id) == id) {
+            Some(i) => i,
+            None => return,
+        };
+
+        let bookmark = if self.buf[i].bookmarked {
+            im_str!("Remove bookmark")
+        } else {
+            im_str!("Bookmark")
+        };
+        if imgui::MenuItem::new(bookmark).build(ui) {
+            self.buf[i].bookmarked = !self.buf[i].bookmarked;
+        }
+        if imgui::MenuItem::new(im_str!("Copy line")).build(ui) {
+            let text = self.join_text(std::iter::once(&self.buf[i]));
+            ui.set_clipboard_text(&imgui::ImString::new(text));
+        }
+        let label = imgui::ImString::new(format!("Copy with \u{b1}{} context", self.copy_context));
+        if imgui::MenuItem::new(&label).build(ui) {
+            let start = i.saturating_sub(self.copy_context);
+            let end = i
+                .saturating_add(self.copy_context)
+                .saturating_add(1)
+                .min(self.buf.len());
+            let text = self.join_text(self.buf.range(start..end));
+            ui.set_clipboard_text(&imgui::ImString::new(text));
+        }
+    }
+
+    fn draw_footer(&self, ui: &imgui::Ui) {
+        let stats = self.stats();
+        let mut footer = format!(
+            "lines: {} | errors: {} | dropped: {}",
+            stats.buffered, self.errors, stats.dropped
+        );
+        if let Some(level) = &self.level {
+            let level = filter_from_u8(level.load(Ordering::Relaxed));
+            footer += &format!(" | level: {}", level);
+        }
+        ui.text(footer);
+
+        ui.same_line(0.);
+        if self.paused {
+            ui.text_colored(self.colors.error, " | [PAUSED]");
+        } else {
+            // The default imgui font has no record symbol
+            ui.text(" | [REC]");
+        }
+    }
+
+    /// Draw the configured buttons, returns whether Clear and Copy were pressed
+    fn draw_toolbar(&mut self, ui: &imgui::Ui) -> (bool, bool) {
+        let mut clear = false;
+        let mut copy = false;
+        let mut first = true;
+
+        for item in self.toolbar.clone() {
+            let shown = match item {
+                ToolbarItem::ShowAll => self
+                    .render_limit
+                    .is_some_and(|limit| self.buf.len() > limit),
+                ToolbarItem::Bookmarks => self.buf.iter().any(|l| l.bookmarked),
+                ToolbarItem::TimeRange => self.time_range.is_some(),
+                _ => true,
+            };
+            if !shown {
+                continue;
+            }
+            if !first {
+                ui.same_line(0.);
+            }
+            first = false;
+
+            match item {
+                ToolbarItem::Options => {
+                    if ui.button(im_str!("Options"), [0., 0.]) {
+                        ui.open_popup(im_str!("Options"));
+                    }
+                }
+                ToolbarItem::Clear => clear = ui.button(im_str!("Clear"), [0., 0.]),
+                ToolbarItem::Copy => copy = ui.button(im_str!("Copy"), [0., 0.]),
+                ToolbarItem::Pause => {
+                    if self.paused {
+                        let color =
+                            ui.push_style_color(imgui::StyleColor::Button, self.colors.error);
+                        if ui.button(im_str!("Resume###pause"), [0., 0.]) {
+                            self.paused = false;
+                            self.scroll_to_bottom = true;
+                        }
+                        color.pop(ui);
+                    } else if ui.button(im_str!("Pause###pause"), [0., 0.]) {
+                        self.paused = true;
+                    }
+                }
+                ToolbarItem::Levels => {
+                    let presets = [
+                        (im_str!("Errors"), LevelFilter::Error),
+                        (im_str!("Warnings+"), LevelFilter::Warn),
+                        (im_str!("All"), LevelFilter::Trace),
+                    ];
+                    for (n, (label, level)) in presets.iter().enumerate() {
+                        if n > 0 {
+                            ui.same_line(0.);
+                        }
+                        let active = self.shown_level == *level;
+                        let color = if active {
+                            let active_color = ui.style_color(imgui::StyleColor::ButtonActive);
+                            Some(ui.push_style_color(imgui::StyleColor::Button, active_color))
+                        } else {
+                            None
+                        };
+                        if ui.button(label, [0., 0.]) && !active {
+                            self.shown_level = *level;
+                            if !self.at_bottom {
+                                self.scroll_anchor = self.top_line;
+                            }
+                        }
+                        if let Some(color) = color {
+                            color.pop(ui);
+                        }
+                    }
+                }
+                ToolbarItem::ShowAll => {
+                    let label = if self.render_all {
+                        im_str!("Show recent###render_all")
+                    } else {
+                        im_str!("Show all###render_all")
+                    };
+                    if ui.button(label, [0., 0.]) {
+                        self.render_all = !self.render_all;
+                    }
+                }
+                ToolbarItem::Filter => {
+                    let recalled = self.filter_active && self.recall_filter(ui);
+                    if recalled {
+                        // The box keeps its own copy of the text while active, refocusing reloads it
+                        ui.set_keyboard_focus_here(imgui::FocusedWidget::Next);
+                    }
+                    let mut changed = ui.input_text(im_str!("Filter"), &mut self.filter).build();
+                    changed |= recalled;
+                    let active = ui.is_item_active();
+                    // Enter takes the focus away from the box, so look at whether it had it before
+                    if self.filter_active
+                        && !active
+                        && ui.is_key_pressed(ui.key_index(imgui::Key::Enter))
+                    {
+                        self.push_filter();
+                    }
+                    self.filter_active = active;
+                    ui.same_line(0.);
+                    changed |= ui.checkbox(im_str!("Fuzzy"), &mut self.fuzzy);
+                    if changed {
+                        // Keep the same content in view rather than the same scroll offset,
+                        // unless we are following the bottom, which autoscroll handles.
+                        if !self.at_bottom {
+                            self.scroll_anchor = self.top_line;
+                        }
+                    }
+                }
+                ToolbarItem::Bookmarks => {
+                    if ui.button(im_str!("Prev bookmark"), [0., 0.]) {
+                        self.scroll_anchor = self.find_bookmark(false);
+                    }
+                    ui.same_line(0.);
+                    if ui.button(im_str!("Next bookmark"), [0., 0.]) {
+                        self.scroll_anchor = self.find_bookmark(true);
+                    }
+                }
+                ToolbarItem::Save => {
+                    if ui.button(im_str!("Save"), [0., 0.]) {
+                        if let Err(e) = self.save_to_file(&self.save_path) {
+                            let message =
+                                format!("could not save to {}: {}", self.save_path.display(), e);
+                            self.report(&message);
+                        }
+                    }
+                }
+                ToolbarItem::Bottom => {
+                    if ui.button(im_str!("Bottom"), [0., 0.]) {
+                        self.scroll_to_bottom = true;
+                    }
+                }
+                ToolbarItem::Separator => ui.text_disabled("|"),
+                ToolbarItem::Rate => {
+                    self.advance_rate(unix_seconds(self.clock.now()));
+                    let rate: Vec<f32> = self.rate.iter().cloned().collect();
+                    // The current second is still filling up
+                    let last = rate[RATE_SECONDS - 2];
+                    let overlay = imgui::ImString::new(format!("{}/s", last));
+                    ui.plot_lines(im_str!("##rate"), &rate)
+                        .overlay_text(&overlay)
+                        .scale_min(0.)
+                        .graph_size([RATE_SECONDS as f32 * 2., ui.frame_height()])
+                        .build();
+                }
+                ToolbarItem::TimeRange => {
+                    let [min, max] = self.time_bounds();
+                    if let Some([from, to]) = &mut self.time_range {
+                        ui.drag_float_range2(im_str!("Time (s)"), from, to)
+                            .min(min)
+                            .max(max)
+                            .speed(0.1)
+                            .build();
+                    }
+                }
+            }
+        }
+
+        (clear, copy)
+    }
+
+    fn draw_tabs(&mut self, ui: &imgui::Ui) {
+        if self.tabs.is_empty() {
+            return;
+        }
+
+        let labels =
+            std::iter::once("All").chain(self.tabs.iter().map(|(label, _)| label.as_str()));
+        let mut clicked = None;
+        for (i, label) in labels.enumerate() {
+            if i > 0 {
+                ui.same_line(0.);
+            }
+            let label = imgui::ImString::new(label);
+            let size = ui.calc_text_size(&label, false, -1.);
+            if imgui::Selectable::new(&label)
+                .selected(i == self.active_tab)
+                .size(size)
+                .build(ui)
+            {
+                clicked = Some(i);
+            }
+        }
+
+        if let Some(i) = clicked {
+            self.active_tab = i;
+        }
+    }
+
+    /// Draw the log lines into the current window, following the bottom if needed
+    fn draw_lines(&mut self, ui: &imgui::Ui) {
+        if self.summary_view {
+            self.draw_summary(ui);
+            return;
+        }
+
+        let style = ui.push_style_var(imgui::StyleVar::ItemSpacing([0., 0.]));
+
+        let scroll_y = ui.scroll_y();
+        let mut anchor = self.scroll_anchor.take();
+        let jumped = anchor.is_some();
+        let mut top_line = None;
+        let mut clicked = None;
+        let marker = self.marker.filter(|_| !self.since_marker);
+        let lines: Vec<_> = self.rendered().collect();
+        let groups = if self.fixed_row_height.is_some() {
+            vec![]
+        } else if self.group_similar {
+            similar::group(&lines, marker)
+        } else {
+            (0..lines.len()).map(|n| n..n + 1).collect()
+        };
+        let mut previous = None;
+        let mut widest = 0f32;
+        let mut toggled = None;
+        let mut draw_line = |record: &LogLine, previous: Option<&LogLine>| {
+            let start = ui.cursor_screen_pos()[0];
+            let clicks = self.draw_line(ui, record, previous);
+            widest = widest.max(ui.item_rect_max()[0] - start);
+            if clicks.expand {
+                toggled = Some(record.id);
+            }
+            clicks.menu
+        };
+        if let Some(height) = self.fixed_row_height {
+            let (top, menu) = self.draw_rows(ui, &lines, height, anchor.take(), scroll_y);
+            top_line = top;
+            clicked = menu;
+        }
+        for group in groups {
+            let group = &lines[group];
+            let (i, record) = group[0];
+            let (last, _) = group[group.len() - 1];
+            if marker == Some(i) {
+                ui.separator();
+            }
+            if anchor.is_some_and(|a| last >= a) {
+                ui.set_scroll_here_y_with_ratio(0.);
+                anchor = None;
+            }
+            if top_line.is_none() && ui.cursor_pos()[1] >= scroll_y {
+                top_line = Some(i);
+            }
+
+            if group.len() == 1 {
+                if draw_line(record, previous) {
+                    clicked = Some(i);
+                }
+                previous = Some(record);
+                continue;
+            }
+
+            let label = imgui::ImString::new(format!(
+                "{} ({} lines)",
+                similar::template(group),
+                group.len()
+            ));
+            let color = record
+                .color
+                .unwrap_or_else(|| self.colors.level(record.level));
+            let text_color = ui.push_style_color(imgui::StyleColor::Text, color);
+            let id = record.id.to_string();
+            imgui::TreeNode::new(ui, &id).label(&label).build(|| {
+                for &(i, record) in group {
+                    if draw_line(record, previous) {
+                        clicked = Some(i);
+                    }
+                    previous = Some(record);
+                }
+            });
+            text_color.pop(ui);
+        }
+        self.top_line = top_line;
+        self.overflows = widest > ui.window_content_region_width();
+        if self.bottom_padding > 0. {
+            ui.dummy([0., self.bottom_padding]);
+        }
+
+        style.pop(ui);
+
+        self.handle_clicks(ui, toggled, clicked);
+
+        self.at_bottom = !jumped && scroll_y >= ui.scroll_max_y();
+        if self.at_bottom {
+            self.unread = 0;
+        }
+        let follow = !self.paused && (self.autoscroll || self.at_bottom);
+        if self.scroll_to_bottom || follow {
+            ui.set_scroll_here_y_with_ratio(1.0);
+            self.scroll_to_bottom = false;
+        } else if self.interactive && self.unread > 0 {
+            self.draw_unread_button(ui, scroll_y);
+        }
+    }
+
+    /// Draw only the rows of `lines` in view, see `set_fixed_row_height`.
+    /// Returns the first line in view and the line that was right clicked.
+    fn draw_rows(
+        &self,
+        ui: &imgui::Ui,
+        lines: &[(usize, &LogLine)],
+        height: f32,
+        anchor: Option<usize>,
+        scroll_y: f32,
+    ) -> (Option<usize>, Option<usize>) {
+        let [x, start_y] = ui.cursor_pos();
+        if let Some(anchor) = anchor {
+            let row = lines.iter().position(|&(i, _)| i >= anchor);
+            let row = row.unwrap_or(lines.len());
+            ui.set_scroll_y(start_y + row as f32 * height);
+        }
+        let first = (((scroll_y - start_y) / height).max(0.) as usize).min(lines.len());
+        let in_view = ((ui.window_size()[1] / height).ceil() as usize).saturating_add(1);
+        let last = first.saturating_add(in_view).min(lines.len());
+
+        let mut clicked = None;
+        for (row, &(i, record)) in lines[first..last].iter().enumerate() {
+            ui.set_cursor_pos([x, start_y + (first + row) as f32 * height]);
+            if self.draw_line(ui, record, None).menu {
+                clicked = Some(i);
+            }
+        }
+        // Take up the room of every row, so the scrollbar covers the whole log
+        ui.set_cursor_pos([x, start_y + lines.len() as f32 * height]);
+        ui.dummy([0., 0.]);
+
+        (lines.get(first).map(|&(i, _)| i), clicked)
+    }
+
+    /// One collapsible header per level, see `set_summary_view`
+    fn draw_summary(&mut self, ui: &imgui::Ui) {
+        let levels = [
+            (Level::Error, "Errors"),
+            (Level::Warn, "Warnings"),
+            (Level::Info, "Info"),
+            (Level::Debug, "Debug"),
+            (Level::Trace, "Trace"),
+        ];
+        let lines: Vec<_> = self.visible().collect();
+        let mut clicked = None;
+        let mut toggled = None;
+        for &(level, name) in &levels {
+            let of_level: Vec<_> = lines.iter().filter(|(_, l)| l.level == level).collect();
+            // The id stays the same as the count changes so the header stays open
+            let label = imgui::ImString::new(format!("{}: {}###{}", name, of_level.len(), name));
+            let text_color = ui.push_style_color(imgui::StyleColor::Text, self.colors.level(level));
+            let open = ui.collapsing_header(&label).build();
+            text_color.pop(ui);
+            if !open {
+                continue;
+            }
+
+            let style = ui.push_style_var(imgui::StyleVar::ItemSpacing([0., 0.]));
+            let mut previous = None;
+            for &&(i, record) in &of_level {
+                let clicks = self.draw_line(ui, record, previous);
+                if clicks.menu {
+                    clicked = Some(i);
+                }
+                if clicks.expand {
+                    toggled = Some(record.id);
+                }
+                previous = Some(record);
+            }
+            style.pop(ui);
+        }
+
+        self.handle_clicks(ui, toggled, clicked);
+    }
+
+    /// Expand or collapse the line with id `toggled`, and open the context menu
+    /// on the line at index `clicked`
+    fn handle_clicks(&mut self, ui: &imgui::Ui, toggled: Option<u64>, clicked: Option<usize>) {
+        if let Some(id) = toggled {
+            if !self.expanded.remove(&id) {
+                self.expanded.insert(id);
+            }
+        }
+        if let Some(i) = clicked.filter(|_| self.interactive) {
+            self.context_line = Some(self.buf[i].id);
+            ui.open_popup(im_str!("Line"));
+        }
+        ui.popup(im_str!("Line"), || self.draw_line_menu(ui));
+    }
+
+    /// A button floating in the bottom right corner that jumps back to the newest lines
+    fn draw_unread_button(&mut self, ui: &imgui::Ui, scroll_y: f32) {
+        // The default imgui font has no arrows
+        let label = imgui::ImString::new(format!("v {} new###unread", self.unread));
+        let text = ui.calc_text_size(&label, true, -1.);
+        let size = [text[0] + 16., text[1] + 8.];
+        let [width, height] = ui.window_size();
+        // Room for the scrollbars
+        let margin = 24.;
+        ui.set_cursor_pos([
+            ui.scroll_x() + width - size[0] - margin,
+            scroll_y + height - size[1] - margin,
+        ]);
+        if ui.button(&label, size) {
+            self.paused = false;
+            self.scroll_to_bottom = true;
+        }
+    }
+
+    /// Draw the log at `pos` (relative to the current window) with the given `size`,
+    /// without a window of its own, e.g. inside a panel of your editor layout.
+    ///
+    /// The toolbar, tabs and footer are drawn inside the rect too.
+    pub fn draw_in_rect(&mut self, ui: &imgui::Ui, pos: [f32; 2], size: [f32; 2]) {
+        self.sync();
+
+        ui.set_cursor_pos(pos);
+        imgui::ChildWindow::new(imgui::Id::Str("log_rect"))
+            .size(size)
+            .scroll_bar(false)
+            .scrollable(false)
+            .build(ui, || {
+                if ui.is_window_focused() {
+                    self.acknowledge();
+                }
+                self.draw_contents(ui);
+            });
+    }
+
+    /// Everything inside the window: the popups, toolbar, tabs, lines and footer
+    fn draw_contents(&mut self, ui: &imgui::Ui) {
+        // Keep the popups and scroll region of several named logs apart,
+        // even when drawn into the same window
+        let name = self.name.clone();
+        let name_id = name.as_ref().map(|name| ui.push_id(name));
+        self.draw_named_contents(ui);
+        if let Some(name_id) = name_id {
+            name_id.pop(ui);
+        }
+    }
+
+    fn draw_named_contents(&mut self, ui: &imgui::Ui) {
+        let interactive = self.interactive;
+        ui.popup(im_str!("Options"), || {
+            ui.checkbox(im_str!("Auto-scroll"), &mut self.autoscroll);
+            let mut colorblind = self.replaced_colors.is_some();
+            if ui.checkbox(im_str!("Color blind safe colors"), &mut colorblind) {
+                if colorblind {
+                    let colors = std::mem::replace(&mut self.colors, LogColors::colorblind_safe());
+                    self.replaced_colors = Some(colors);
+                } else if let Some(colors) = self.replaced_colors.take() {
+                    self.colors = colors;
+                }
+            }
+            ui.separator();
+            if ui.button(im_str!("Set marker"), [0., 0.]) {
+                self.set_marker();
+            }
+            ui.checkbox(im_str!("Only since marker"), &mut self.since_marker);
+            let mut by_time = self.time_range.is_some();
+            if ui.checkbox(im_str!("Filter by time"), &mut by_time) {
+                self.time_range = if by_time {
+                    Some(self.time_bounds())
+                } else {
+                    None
+                };
+            }
+            ui.separator();
+            let mut context = self.copy_context.min(i32::MAX as usize) as i32;
+            if ui.input_int(im_str!("Copy context"), &mut context).build() {
+                self.copy_context = context.max(0) as usize;
+            }
+            // Indexed by `LevelFilter as usize`
+            let levels = [
+                im_str!("Off"),
+                im_str!("Error"),
+                im_str!("Warn"),
+                im_str!("Info"),
+                im_str!("Debug"),
+                im_str!("Trace"),
+            ];
+            let floor = filter_from_u8(self.capture_floor.load(Ordering::Relaxed));
+            let mut floor = floor as usize;
+            if imgui::ComboBox::new(im_str!("Keep down to"))
+                .build_simple_string(ui, &mut floor, &levels)
+            {
+                self.set_capture_floor(filter_from_u8(floor as u8));
+            }
+            ui.separator();
+            if ui.button(im_str!("Reset view"), [0., 0.]) {
+                self.reset_view();
+            }
+        });
+
+        let show_toolbar = interactive && self.show_toolbar;
+        let (clear, copy) = if show_toolbar {
+            self.draw_toolbar(ui)
+        } else {
+            (false, false)
+        };
+
+        if interactive {
+            self.draw_tabs(ui);
+        }
+
+        // Resolve the buttons before drawing anything
+        if let Some(text) = self.copy_and_clear(copy, clear) {
+            ui.set_clipboard_text(&imgui::ImString::new(text));
+        }
+
+        if show_toolbar || interactive && !self.tabs.is_empty() {
+            ui.separator();
+        }
+        // Separate id per tab so each one remembers its own scroll position
+        let tab_id = ui.push_id(self.active_tab as i32);
+        let mut child_size = self.child_size;
+        if self.show_footer && child_size[1] == 0. {
+            // Fill the window, minus room for the footer
+            child_size[1] = -ui.text_line_height_with_spacing();
+        }
+        let child = imgui::ChildWindow::new(imgui::Id::Str("scrolling"))
+            .size(child_size)
+            .horizontal_scrollbar(self.overflows);
+        if self.embedded {
+            self.draw_lines(ui);
+        } else {
+            child.build(ui, || self.draw_lines(ui));
+        }
+        tab_id.pop(ui);
+
+        if self.show_footer {
+            self.draw_footer(ui);
+        }
+    }
+
+    /// Receive new lines, then draw the log in `window`.
+    ///
+    /// Keep calling this every frame even while the window is collapsed or closed:
+    /// lines are received before `window` is built, so they are still buffered
+    /// (and errors still counted for `has_unseen_errors`) while nothing is drawn.
+    pub fn build(&mut self, ui: &imgui::Ui, window: imgui::Window) {
+        // Outside of the window's closure, which doesn't run while it is collapsed
+        self.sync();
+
+        let window = if self.fit_width && self.window_size[1] > 0. {
+            self.fit_width = false;
+            let widest = self
+                .visible()
+                .map(|(_, l)| ui.calc_text_size(&imgui::ImString::new(&*l.text), false, -1.)[0])
+                .fold(0., f32::max);
+            // Room for the window padding and vertical scrollbar
+            let width = widest + 40.;
+            window.size([width, self.window_size[1]], imgui::Condition::Always)
+        } else {
+            window
+        };
+
+        let flash = if self.flash > 0 {
+            self.flash -= 1;
+            // Blink rather than holding a solid color, it is easier to notice
+            (self.flash % 20 >= 10).then(|| {
+                (
+                    ui.push_style_color(imgui::StyleColor::TitleBg, self.colors.error),
+                    ui.push_style_color(imgui::StyleColor::TitleBgActive, self.colors.error),
+                )
+            })
+        } else {
+            None
+        };
+
+        window.build(ui, || {
+            self.window_size = ui.window_size();
+            if ui.is_window_focused() {
+                self.acknowledge();
+            }
+            self.draw_contents(ui);
+        });
+        if let Some((title_bg, title_bg_active)) = flash {
+            title_bg_active.pop(ui);
+            title_bg.pop(ui);
+        }
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// The binary data attached to `line`, see `LogLine::with_attachment`
+#[cfg(feature = "attachments")]
+fn attachment(line: &LogLine) -> Option<&[u8]> {
+    line.attachment.as_deref()
+}
+
+#[cfg(not(feature = "attachments"))]
+fn attachment(_: &LogLine) -> Option<&[u8]> {
+    None
+}
+
+/// Black or white, whichever is easier to read on `background`
+fn contrasting(background: [f32; 4]) -> [f32; 4] {
+    let luminance = 0.299 * background[0] + 0.587 * background[1] + 0.114 * background[2];
+    if luminance > 0.5 {
+        [0., 0., 0., 1.]
+    } else {
+        [1., 1., 1., 1.]
+    }
+}
+
+/// The level of a line written by the default formatter, `Info` if there isn't one
+fn guess_level(text: &str) -> Level {
+    text.split_whitespace()
+        .filter_map(|word| word.strip_suffix(':'))
+        .find_map(|word| word.parse().ok())
+        .unwrap_or(Level::Info)
+}
+
+/// True if every character of `pattern` appears in `text`, in order, ignoring case and spaces
+fn fuzzy_matches(pattern: &str, text: &str) -> bool {
+    let mut text = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .all(|p| text.any(|t| t == p))
+}
+
+/// The first line of `text`, shortened to fit in `width` pixels,
+/// and whether anything had to be cut
+fn fit_width<'a>(ui: &imgui::Ui, text: &'a str, width: f32) -> (Cow<'a, str>, bool) {
+    const ELLIPSIS: &str = "...";
+    let text_width = |text: &str| ui.calc_text_size(&imgui::ImString::new(text), false, -1.)[0];
+    let mut lines = text.lines();
+    let first = lines.next().unwrap_or("");
+    let multiline = lines.next().is_some();
+    if !multiline && text_width(first) <= width {
+        return (Cow::Borrowed(first), false);
+    }
+
+    // Find the most characters that still fit along with the ellipsis
+    let ends: Vec<_> = first
+        .char_indices()
+        .map(|(i, _)| i)
+        .chain(Some(first.len()))
+        .collect();
+    let (mut fits, mut too_long) = (0, ends.len());
+    while too_long - fits > 1 {
+        let mid = (fits + too_long) / 2;
+        if text_width(&first[..ends[mid]]) + text_width(ELLIPSIS) <= width {
+            fits = mid;
+        } else {
+            too_long = mid;
+        }
+    }
+    (
+        Cow::Owned(format!("{}{}", &first[..ends[fits]], ELLIPSIS)),
+        true,
+    )
+}
+
+/// `text` padded or shortened in the middle to exactly `width` characters,
+/// and whether it had to be shortened
+fn fit_column(text: &str, width: usize) -> (String, bool) {
+    let len = text.chars().count();
+    if len <= width {
+        return (format!("{:width$}", text, width = width), false);
+    }
+
+    // The default imgui font has no '…'
+    const ELLIPSIS: &str = "..";
+    if width <= ELLIPSIS.len() {
+        return (text.chars().take(width).collect(), true);
+    }
+    let keep = width - ELLIPSIS.len();
+    let head: String = text.chars().take(keep - keep / 2).collect();
+    let tail: String = text.chars().skip(len - keep / 2).collect();
+    (format!("{}{}{}", head, ELLIPSIS, tail), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window_with(texts: &[&str]) -> LogWindow {
+        let (_, receiver) = mpsc::sync_channel(0);
+        let mut window = LogWindow::new(receiver);
+        window.extend(
+            texts
+                .iter()
+                .map(|text| LogLine::new(Level::Info, "test", text.to_string())),
+        );
+        window
+    }
+
+    #[test]
+    fn visible_text_only_joins_matching_lines() {
+        let mut window = window_with(&["loaded foo", "saved bar", "loaded baz"]);
+        window.filter.push_str("loaded");
+        assert_eq!(window.visible_text(), "loaded foo\nloaded baz");
+    }
+
+    #[test]
+    fn sync_receives_lines_without_drawing() {
+        let (sender, receiver) = mpsc::sync_channel(16);
+        let mut window = LogWindow::new(receiver);
+        sender
+            .send(LogLine::new(Level::Info, "test", "loading".into()))
+            .unwrap();
+        sender
+            .send(LogLine::new(Level::Error, "test", "failed".into()))
+            .unwrap();
+
+        // What `build` does for a collapsed window, before the closure that would draw it
+        window.sync();
+        let texts: Vec<_> = window.lines().iter().map(|l| &*l.text).collect();
+        assert_eq!(texts, ["loading", "failed"]);
+        assert!(window.has_unseen_errors());
+    }
+
+    #[test]
+    fn extend_stays_within_max_lines() {
+        let mut window = window_with(&[]);
+        window.set_max_lines(Some(2));
+        let capacity = window.lines().capacity();
+        window.extend((0..100).map(|n| LogLine::new(Level::Info, "test", n.to_string())));
+        let texts: Vec<_> = window.lines().iter().map(|l| &*l.text).collect();
+        assert_eq!(texts, ["98", "99"]);
+        assert_eq!(window.lines().capacity(), capacity);
+    }
+
+    #[test]
+    fn huge_max_lines_is_not_allocated_up_front() {
+        let mut window = window_with(&["kept"]);
+        window.set_max_lines(Some(usize::MAX));
+        assert!(window.lines().capacity() <= PREALLOCATED_LINES * 2);
+        window.extend(Some(LogLine::new(Level::Info, "test", "added".into())));
+        assert_eq!(window.lines().len(), 2);
+    }
+
+    #[test]
+    fn fit_column_never_exceeds_width() {
+        assert_eq!(fit_column("target", 8), ("target  ".to_string(), false));
+        assert_eq!(
+            fit_column("my_crate::module", 8),
+            ("my_..ule".to_string(), true)
+        );
+        assert_eq!(fit_column("target", 1), ("t".to_string(), true));
+        assert_eq!(fit_column("target", 0), (String::new(), true));
+    }
+
+    #[test]
+    fn copy_and_clear_copies_before_clearing() {
+        let mut window = window_with(&["first", "second"]);
+        let copied = window.copy_and_clear(true, true);
+        assert_eq!(copied.as_deref(), Some("first\nsecond"));
+        assert!(window.lines().is_empty());
+    }
+}